@@ -0,0 +1,163 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    mem,
+};
+
+use crate::{
+    cycle::Cycle,
+    grid::{Grid, Vector},
+    lex_ord::LexOrd,
+};
+
+/// What the dense and sparse engines have in common: they can be stepped, and each state has a key
+/// which identifies it, which is enough to find fixpoints and cycles.
+pub trait Automaton: Sized {
+    type Key: Eq + Hash;
+
+    fn step(&mut self);
+
+    fn key(&self) -> Self::Key;
+
+    /// Steps until the state stops changing. Returns the number of steps which changed the state.
+    fn run_to_fixpoint(&mut self) -> usize {
+        let mut key = self.key();
+        let mut steps = 0;
+        loop {
+            self.step();
+            let next = self.key();
+            if next == key {
+                return steps;
+            }
+            key = next;
+            steps += 1;
+        }
+    }
+
+    /// Steps until we see a state we've seen before, so the state is known at any step.
+    fn find_cycle(self) -> Cycle<Self::Key> {
+        Cycle::find(self, Self::step, Self::key)
+    }
+}
+
+/// A cellular automaton over a dense grid, where every cell is updated simultaneously by a rule
+/// which sees the current value of the cell and the values of its neighbours.
+pub struct Dense<T, R> {
+    pub cells: Grid<T>,
+    scrap: Grid<T>,
+    neighbourhood: Grid<Vec<Vector>>,
+    rule: R,
+}
+
+impl<T, R> Dense<T, R>
+where
+    T: Clone,
+    R: for<'a> FnMut(&'a T, &mut dyn Iterator<Item = &'a T>) -> T,
+{
+    /// The neighbourhood is evaluated once for each cell up front, so it can depend on the initial
+    /// state of the grid (e.g. line of sight past cells which never change). Neighbours outside the
+    /// grid are ignored.
+    pub fn new<N, I>(cells: Grid<T>, mut neighbourhood: N, rule: R) -> Self
+    where
+        N: FnMut(Vector) -> I,
+        I: IntoIterator<Item = Vector>,
+    {
+        let neighbourhood = Grid::from_iter(
+            cells.size,
+            cells.keys().map(|pos| {
+                neighbourhood(pos)
+                    .into_iter()
+                    .filter(|&v| cells.contains_key(v))
+                    .collect()
+            }),
+        );
+        Self {
+            scrap: cells.clone(),
+            cells,
+            neighbourhood,
+            rule,
+        }
+    }
+}
+
+impl<T, R> Automaton for Dense<T, R>
+where
+    T: Clone + Eq + Hash,
+    R: for<'a> FnMut(&'a T, &mut dyn Iterator<Item = &'a T>) -> T,
+{
+    type Key = Grid<T>;
+
+    fn step(&mut self) {
+        let Self {
+            cells,
+            scrap,
+            neighbourhood,
+            rule,
+        } = self;
+        for (pos, cell) in &*cells {
+            scrap[pos] = rule(cell, &mut neighbourhood[pos].iter().map(|&v| &cells[v]));
+        }
+        mem::swap(cells, scrap);
+    }
+
+    fn key(&self) -> Grid<T> {
+        self.cells.clone()
+    }
+}
+
+/// A cellular automaton over a sparse set of live points, in any number of dimensions. The rule
+/// decides whether a point is live in the next generation given whether it's live now and how many
+/// of its neighbours are live. The neighbourhood must be symmetric.
+pub struct Sparse<P, N, R> {
+    pub live: HashSet<P>,
+    neighbourhood: N,
+    rule: R,
+}
+
+impl<P, N, I, R> Sparse<P, N, R>
+where
+    P: Copy + Eq + Hash,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = P>,
+    R: FnMut(bool, usize) -> bool,
+{
+    pub fn new(live: HashSet<P>, neighbourhood: N, rule: R) -> Self {
+        Self {
+            live,
+            neighbourhood,
+            rule,
+        }
+    }
+}
+
+/// Live points are sorted for the key, since a `HashSet` can't be hashed.
+impl<P, N, I, R> Automaton for Sparse<P, N, R>
+where
+    P: Copy + Eq + Hash,
+    LexOrd<P>: Ord,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = P>,
+    R: FnMut(bool, usize) -> bool,
+{
+    type Key = Vec<LexOrd<P>>;
+
+    fn step(&mut self) {
+        let mut counts: HashMap<P, usize> = self.live.iter().map(|&p| (p, 0)).collect();
+        for &p in &self.live {
+            for q in (self.neighbourhood)(p) {
+                *counts.entry(q).or_default() += 1;
+            }
+        }
+        self.live = counts
+            .into_iter()
+            .filter(|&(p, count)| (self.rule)(self.live.contains(&p), count))
+            .map(|(p, _)| p)
+            .collect();
+    }
+
+    fn key(&self) -> Vec<LexOrd<P>> {
+        let mut live: Vec<_> = self.live.iter().copied().map(LexOrd).collect();
+        live.sort_unstable();
+        live
+    }
+}
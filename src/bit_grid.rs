@@ -0,0 +1,234 @@
+use std::{
+    array,
+    collections::HashSet,
+    hash::BuildHasher,
+    ops::{BitAnd, BitOr, BitXor, Not, Range},
+};
+
+use crate::grid::{Adjacent, Bounds, Grid, Vector};
+
+/// A rectangular grid of bools, packed in to 64 cells per word so that whole grids can be combined
+/// a word at a time. Cells are indexed from zero like `Grid`. Bits past the end of each row are
+/// always zero.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    pub size: Vector,
+    row_len: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new<V: Into<Vector>>(size: V) -> Self {
+        let size: Vector = size.into();
+        let row_len = usize::try_from(size.x).unwrap().div_ceil(64);
+        Self {
+            size,
+            row_len,
+            words: vec![0; row_len * usize::try_from(size.y).unwrap()],
+        }
+    }
+
+    // The index of the word containing the given cell, and the bit within that word.
+    fn locate(&self, v: Vector) -> Option<(usize, u32)> {
+        if 0 <= v.x && v.x < self.size.x && 0 <= v.y && v.y < self.size.y {
+            let x = usize::try_from(v.x).unwrap();
+            let y = usize::try_from(v.y).unwrap();
+            Some((y * self.row_len + x / 64, u32::try_from(x % 64).unwrap()))
+        } else {
+            None
+        }
+    }
+
+    /// Cells outside the grid are false.
+    pub fn get<V: Into<Vector>>(&self, v: V) -> bool {
+        self.locate(v.into())
+            .is_some_and(|(i, bit)| self.words[i] >> bit & 1 == 1)
+    }
+
+    pub fn insert<V: Into<Vector>>(&mut self, v: V) {
+        let (i, bit) = self.locate(v.into()).unwrap();
+        self.words[i] |= 1 << bit;
+    }
+
+    /// The number of true cells.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|w| usize::try_from(w.count_ones()).unwrap())
+            .sum()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Vector> + '_ {
+        (0..self.size.y).flat_map(move |y| {
+            (0..self.size.x)
+                .map(move |x| Vector::new(x, y))
+                .filter(|&v| self.get(v))
+        })
+    }
+
+    /// Moves every cell by `dir`. Cells which move off the grid are lost, and cells which move on
+    /// to the grid are false.
+    pub fn shift(&self, dir: Vector) -> Self {
+        let mut res = Self::new(self.size);
+        let (q, r) = (dir.x.abs() / 64, u32::try_from(dir.x.abs() % 64).unwrap());
+        let q = usize::try_from(q).unwrap();
+        for y in 0..self.size.y {
+            let src_y = y - dir.y;
+            if src_y < 0 || src_y >= self.size.y {
+                continue;
+            }
+            let src = &self.words[self.row(src_y)];
+            let word = |i: Option<usize>| i.and_then(|i| src.get(i)).copied().unwrap_or(0);
+            let dst = res.row(y);
+            for (i, w) in res.words[dst].iter_mut().enumerate() {
+                *w = if dir.x >= 0 {
+                    word(i.checked_sub(q)) << r
+                        | if r == 0 {
+                            0
+                        } else {
+                            word(i.checked_sub(q + 1)) >> (64 - r)
+                        }
+                } else {
+                    word(Some(i + q)) >> r
+                        | if r == 0 {
+                            0
+                        } else {
+                            word(Some(i + q + 1)) << (64 - r)
+                        }
+                };
+            }
+        }
+        res.clear_padding();
+        res
+    }
+
+    /// For each `n` from 0 to 8, the cells which have exactly `n` of their 8 neighbours true.
+    pub fn neighbour_counts(&self) -> [Self; 9] {
+        // Add up the 8 shifted grids as 4 bit binary numbers, a bit plane at a time.
+        let mut planes: [Self; 4] = array::from_fn(|_| Self::new(self.size));
+        for dir in Vector::zeros().adjacent8() {
+            let mut carry = self.shift(dir);
+            for plane in &mut planes {
+                let next_carry = &*plane & &carry;
+                *plane = &*plane ^ &carry;
+                carry = next_carry;
+            }
+        }
+        array::from_fn(|n| {
+            planes
+                .iter()
+                .enumerate()
+                .fold(!&Self::new(self.size), |res, (i, plane)| {
+                    if n >> i & 1 == 1 {
+                        &res & plane
+                    } else {
+                        &res & &!plane
+                    }
+                })
+        })
+    }
+
+    fn row(&self, y: i64) -> Range<usize> {
+        let start = usize::try_from(y).unwrap() * self.row_len;
+        start..start + self.row_len
+    }
+
+    fn clear_padding(&mut self) {
+        let tail = self.size.x % 64;
+        if tail != 0 {
+            for row in self.words.chunks_mut(self.row_len) {
+                *row.last_mut().unwrap() &= (1 << tail) - 1;
+            }
+        }
+    }
+
+    fn zip_with<F: Fn(u64, u64) -> u64>(&self, other: &Self, f: F) -> Self {
+        assert_eq!(self.size, other.size);
+        Self {
+            size: self.size,
+            row_len: self.row_len,
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: Self) -> BitGrid {
+        self.zip_with(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, other: Self) -> BitGrid {
+        self.zip_with(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, other: Self) -> BitGrid {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut res = self.zip_with(self, |a, _| !a);
+        res.clear_padding();
+        res
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut res = Self::new(grid.size);
+        for v in grid.points() {
+            res.insert(v);
+        }
+        res
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        Grid::from_iter(
+            bits.size,
+            (0..bits.size.y).flat_map(|y| (0..bits.size.x).map(move |x| bits.get([x, y]))),
+        )
+    }
+}
+
+/// The grid is just big enough to hold every point, so no points gives an empty grid. Points must
+/// have non-negative coordinates.
+impl<S: BuildHasher> From<&HashSet<Vector, S>> for BitGrid {
+    fn from(points: &HashSet<Vector, S>) -> Self {
+        if points.is_empty() {
+            return Self::new(Vector::zeros());
+        }
+        let bounds: Bounds<2> = points.iter().copied().collect();
+        assert!(bounds.min.x >= 0 && bounds.min.y >= 0);
+        let mut res = Self::new(bounds.max.add_scalar(1));
+        for &v in points {
+            res.insert(v);
+        }
+        res
+    }
+}
+
+impl<S: BuildHasher + Default> From<&BitGrid> for HashSet<Vector, S> {
+    fn from(bits: &BitGrid) -> Self {
+        bits.points().collect()
+    }
+}
@@ -0,0 +1,63 @@
+use std::{collections::HashMap, hash::Hash, rc::Rc};
+
+/// A sequence of keys which eventually repeats: the first `start` keys lead in to a loop of `len`
+/// keys which goes round forever.
+pub struct Cycle<K> {
+    pub start: usize,
+    pub len: usize,
+    keys: Vec<Rc<K>>,
+}
+
+impl<K> Cycle<K>
+where
+    K: Eq + Hash,
+{
+    /// Steps `state` until we see a key we've seen before, remembering the key for every step on
+    /// the way. The key can be the whole state, or just the part of it we care about. Keys can be
+    /// big (whole grids) so each one is shared between the lookup table and the sequence.
+    pub fn find<S, F, H>(mut state: S, mut step: F, mut key: H) -> Self
+    where
+        F: FnMut(&mut S),
+        H: FnMut(&S) -> K,
+    {
+        let mut seen: HashMap<Rc<K>, usize> = HashMap::new();
+        let mut keys = Vec::new();
+        loop {
+            let k = Rc::new(key(&state));
+            if let Some(&start) = seen.get(&k) {
+                return Self {
+                    start,
+                    len: keys.len() - start,
+                    keys,
+                };
+            }
+            seen.insert(Rc::clone(&k), keys.len());
+            keys.push(k);
+            step(&mut state);
+        }
+    }
+
+    /// The key after `n` steps, for any `n` however big.
+    pub fn get(&self, n: usize) -> &K {
+        if n < self.start {
+            &self.keys[n]
+        } else {
+            &self.keys[self.start + (n - self.start) % self.len]
+        }
+    }
+
+    /// The value after `n` steps of something which changes by the same amount every time round
+    /// the loop, like the height of a growing tower. `value` gives it after any step up to and
+    /// including `start + len`, where the loop first comes back round.
+    pub fn extrapolate<F>(&self, n: usize, mut value: F) -> i64
+    where
+        F: FnMut(usize) -> i64,
+    {
+        if n < self.start {
+            return value(n);
+        }
+        let laps = i64::try_from((n - self.start) / self.len).unwrap();
+        let lap = value(self.start + self.len) - value(self.start);
+        value(self.start + (n - self.start) % self.len) + laps * lap
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use crate::{grid::Vector, search};
+
+/// A step from one node of a `Graph` to another.
+pub struct Edge {
+    pub to: usize,
+    pub len: usize,
+    /// Cells strictly between the two nodes which were marked as interesting while building the
+    /// graph (e.g. doors).
+    pub crossed: Vec<Vector>,
+}
+
+/// A compact graph between points of interest in a maze, with nodes identified by dense indices so
+/// that sets of nodes can be represented as bitsets.
+pub struct Graph {
+    pub nodes: Vec<Vector>,
+    pub index: HashMap<Vector, usize>,
+    pub edges: Vec<Vec<Edge>>,
+}
+
+impl Graph {
+    /// Collapses a maze into a graph between the given nodes. `adjacent` gives the cells we can move
+    /// to from a given cell. There's an edge from one node to another if we can reach the second
+    /// without passing through any other node, and the edge follows the shortest such route,
+    /// recording the cells along the way where `crossed` holds.
+    pub fn from_maze<N, A, C>(nodes: N, mut adjacent: A, mut crossed: C) -> Self
+    where
+        N: IntoIterator<Item = Vector>,
+        A: FnMut(&Vector, &mut dyn FnMut(Vector)),
+        C: FnMut(Vector) -> bool,
+    {
+        let nodes: Vec<Vector> = nodes.into_iter().collect();
+        let index: HashMap<Vector, usize> =
+            nodes.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+        let edges = nodes
+            .iter()
+            .map(|&start| {
+                let mut prev = HashMap::new();
+                let mut edges = Vec::new();
+                for (pos, p) in search::breadth_first(
+                    (start, start),
+                    |&(pos, _), push| {
+                        if pos == start || !index.contains_key(&pos) {
+                            adjacent(&pos, &mut |v| push((v, pos)));
+                        }
+                    },
+                    search::hash_filter(|&(pos, _): &(Vector, Vector)| pos),
+                ) {
+                    prev.insert(pos, p);
+                    if pos != start && index.contains_key(&pos) {
+                        let mut len = 0;
+                        let mut crossed_cells = Vec::new();
+                        let mut v = pos;
+                        while v != start {
+                            v = prev[&v];
+                            len += 1;
+                            if v != start && crossed(v) {
+                                crossed_cells.push(v);
+                            }
+                        }
+                        edges.push(Edge {
+                            to: index[&pos],
+                            len,
+                            crossed: crossed_cells,
+                        });
+                    }
+                }
+                edges
+            })
+            .collect();
+
+        Self {
+            nodes,
+            index,
+            edges,
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use std::{
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     iter,
     ops::{Index, IndexMut},
 };
 
 use nalgebra::SVector;
+use num::{Num, Zero};
+
+use crate::{freqs::Freqs, search};
 
 pub type Vector = nalgebra::Vector2<i64>;
 pub type Vector3 = nalgebra::Vector3<i64>;
@@ -12,6 +16,10 @@ pub type Vector4 = nalgebra::Vector4<i64>;
 
 pub type Turn = nalgebra::Matrix2<i64>;
 
+/// One of the 24 orientation preserving rotations of 3D space which map the axes on to the axes.
+/// Compose rotations with `*` and invert them with `transpose`.
+pub type Rotation3 = nalgebra::Matrix3<i64>;
+
 pub const NW: Vector = Vector::new(-1, -1);
 pub const N: Vector = Vector::new(0, -1);
 pub const NE: Vector = Vector::new(1, -1);
@@ -70,16 +78,166 @@ impl Adjacent3 for Vector3 {
     }
 }
 
+/// Lengths of integer vectors. Use e.g. `(a - b).manhattan()` for the distance between two points.
+pub trait Metric {
+    fn manhattan(&self) -> i64;
+    fn chebyshev(&self) -> i64;
+    fn euclidean_squared(&self) -> i64;
+}
+
+impl<const D: usize> Metric for SVector<i64, D> {
+    fn manhattan(&self) -> i64 {
+        self.abs().sum()
+    }
+
+    fn chebyshev(&self) -> i64 {
+        self.abs().max()
+    }
+
+    fn euclidean_squared(&self) -> i64 {
+        self.dot(self)
+    }
+}
+
+/// The diamond of points within manhattan distance `r` of `centre`, in reading order.
+pub fn manhattan_ball(centre: Vector, r: i64) -> impl Iterator<Item = Vector> {
+    (-r..=r).flat_map(move |y| {
+        let w = r - y.abs();
+        (-w..=w).map(move |x| centre + Vector::new(x, y))
+    })
+}
+
+/// The points at exactly manhattan distance `r` from `centre`, in reading order.
+#[allow(dead_code)]
+pub fn manhattan_sphere(centre: Vector, r: i64) -> impl Iterator<Item = Vector> {
+    (-r..=r).flat_map(move |y| {
+        let w = r - y.abs();
+        (-w..=w)
+            .step_by(usize::try_from(2 * w).unwrap().max(1))
+            .map(move |x| centre + Vector::new(x, y))
+    })
+}
+
+/// The square of points within chebyshev distance `r` of `centre`, in reading order.
+#[allow(dead_code)]
+pub fn chebyshev_ball(centre: Vector, r: i64) -> impl Iterator<Item = Vector> {
+    (-r..=r).flat_map(move |y| (-r..=r).map(move |x| centre + Vector::new(x, y)))
+}
+
+/// The points at exactly chebyshev distance `r` from `centre`, in reading order.
+#[allow(dead_code)]
+pub fn chebyshev_sphere(centre: Vector, r: i64) -> impl Iterator<Item = Vector> {
+    (-r..=r).flat_map(move |y| {
+        let step = if y.abs() == r { 1 } else { 2 * r };
+        (-r..=r)
+            .step_by(usize::try_from(step).unwrap())
+            .map(move |x| centre + Vector::new(x, y))
+    })
+}
+
+/// All 24 rotations, starting with the identity.
+pub fn rotations3() -> impl Iterator<Item = Rotation3> {
+    // Each rotation is a permutation of the axes, with some signs flipped. Half of these are
+    // reflections, which we can recognise by their axes being left-handed.
+    [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ]
+    .into_iter()
+    .flat_map(|perm| {
+        [1, -1].into_iter().flat_map(move |x| {
+            [1, -1].into_iter().flat_map(move |y| {
+                [1, -1].into_iter().map(move |z| {
+                    let mut m = Rotation3::zeros();
+                    for (row, (col, sign)) in perm.into_iter().zip([x, y, z]).enumerate() {
+                        m[(row, col)] = sign;
+                    }
+                    m
+                })
+            })
+        })
+    })
+    .filter(|m| m.column(0).cross(&m.column(1)) == m.column(2))
+}
+
+/// Finds a rotation and translation which map at least `k` of the `floating` points on to `fixed`
+/// points, such that `rotation * floating + translation` is in `fixed`.
+pub fn align3(fixed: &[Vector3], floating: &[Vector3], k: usize) -> Option<(Rotation3, Vector3)> {
+    rotations3().find_map(|rotation| {
+        fixed
+            .iter()
+            .flat_map(|&a| floating.iter().map(move |&b| a - rotation * b))
+            .freqs()
+            .into_iter()
+            .find(|&(_, freq)| freq >= k)
+            .map(|(translation, _)| (rotation, translation))
+    })
+}
+
 pub fn reading_ord_key(v: Vector) -> [i64; 2] {
     [v.y, v.x]
 }
 
+/// The smallest lattice step in the same direction as `v`. `Z` has no direction, so stays as it is.
+pub fn primitive(v: Vector) -> Vector {
+    v / num::integer::gcd(v.x, v.y).max(1)
+}
+
+/// Every lattice point which lies exactly on the line segment from `s` to `t`, inclusive.
 pub fn line_segment(s: Vector, t: Vector) -> impl DoubleEndedIterator<Item = Vector> {
-    let len = (t - s).abs().max();
-    let dir = (t - s) / len;
+    let len = num::integer::gcd(t.x - s.x, t.y - s.y);
+    let dir = (t - s) / len.max(1);
     (0..=len).map(move |i| s + i * dir)
 }
 
+/// The cells covered by drawing a line from `s` to `t`, inclusive, where each step moves to one of
+/// the 8 adjacent cells. <https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm>
+#[allow(dead_code)]
+pub fn bresenham(s: Vector, t: Vector) -> impl Iterator<Item = Vector> {
+    let step = (t - s).map(i64::signum);
+    let dx = (t.x - s.x).abs();
+    let dy = -(t.y - s.y).abs();
+    let mut err = dx + dy;
+    let mut next = Some(s);
+    iter::from_fn(move || {
+        let v = next?;
+        next = (v != t).then(|| {
+            let mut w = v;
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                w.x += step.x;
+            }
+            if e2 <= dx {
+                err += dx;
+                w.y += step.y;
+            }
+            w
+        });
+        Some(v)
+    })
+}
+
+/// Every point from `origin` (exclusive) in steps of `dir`, until we leave `bounds`.
+pub fn ray(origin: Vector, dir: Vector, bounds: Bounds<2>) -> impl Iterator<Item = Vector> {
+    iter::successors(Some(origin + dir), move |&v| Some(v + dir))
+        .take_while(move |&v| bounds.contains(v))
+}
+
+/// Orders directions by their angle clockwise from straight up (with y increasing downwards).
+/// Directions which differ only in length are equal.
+pub fn cmp_clockwise(a: Vector, b: Vector) -> Ordering {
+    // Directions in the right half, including straight up, come first.
+    let half = |v: Vector| !(v.x > 0 || v.x == 0 && v.y < 0);
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| 0.cmp(&(a.x * b.y - a.y * b.x)))
+}
+
 pub fn scan(s: &str) -> impl Iterator<Item = (Vector, char)> {
     let mut v = Z;
     s.chars().filter_map(move |c| {
@@ -95,7 +253,115 @@ pub fn scan(s: &str) -> impl Iterator<Item = (Vector, char)> {
     })
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Where east, south, and the outward normal of a face of a cube point in 3D.
+#[derive(Clone, Copy)]
+struct CubeFace {
+    e: Vector3,
+    s: Vector3,
+    n: Vector3,
+}
+
+impl CubeFace {
+    fn dir(&self, dir: Vector) -> Vector3 {
+        self.e * dir.x + self.s * dir.y
+    }
+
+    // The orientation of the neighbouring face in the given direction once we fold the net.
+    fn fold(&self, dir: Vector) -> Self {
+        match crate::cast::vector_to_char(dir) {
+            'N' => Self {
+                e: self.e,
+                s: self.n,
+                n: -self.s,
+            },
+            'E' => Self {
+                e: -self.n,
+                s: self.s,
+                n: self.e,
+            },
+            'S' => Self {
+                e: self.e,
+                s: -self.n,
+                n: self.s,
+            },
+            'W' => Self {
+                e: self.n,
+                s: self.s,
+                n: -self.e,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Folds one of the 11 nets of a cube, given as the points it covers, into a cube with the given
+/// side length. Returns a map from `(pos, dir)` for each step which would walk off the edge of the
+/// net, to the `(pos, dir)` we end up with after walking over the corresponding edge of the cube.
+pub fn cube_net_portals<I: IntoIterator<Item = Vector>>(
+    points: I,
+    side_len: i64,
+) -> HashMap<(Vector, Vector), (Vector, Vector)> {
+    let points: HashSet<Vector> = points.into_iter().collect();
+    let face_of = |v: Vector| v.map(|x| x.div_euclid(side_len));
+
+    let start = face_of(
+        points
+            .iter()
+            .copied()
+            .min_by_key(|&v| reading_ord_key(v))
+            .unwrap(),
+    );
+    let mut faces = HashMap::from([(
+        start,
+        CubeFace {
+            e: Vector3::x(),
+            s: Vector3::y(),
+            n: Vector3::z(),
+        },
+    )]);
+    let mut stack = vec![start];
+    while let Some(face) = stack.pop() {
+        for dir in [N, E, S, W] {
+            let next = face + dir;
+            if !faces.contains_key(&next) && points.contains(&(next * side_len)) {
+                faces.insert(next, faces[&face].fold(dir));
+                stack.push(next);
+            }
+        }
+    }
+    assert_eq!(faces.len(), 6, "not a cube net");
+
+    // Position on the surface of a cube centred on the origin, with all coordinates doubled so that
+    // cell centres are integers.
+    let surface = |v: Vector| {
+        let face = &faces[&face_of(v)];
+        let local = v - face_of(v) * side_len;
+        face.n * side_len
+            + face.e * (2 * local.x - side_len + 1)
+            + face.s * (2 * local.y - side_len + 1)
+    };
+    let unfold: HashMap<Vector3, Vector> = points.iter().map(|&v| (surface(v), v)).collect();
+
+    let mut res = HashMap::new();
+    for &v in &points {
+        for dir in [N, E, S, W] {
+            if !points.contains(&(v + dir)) {
+                let face = &faces[&face_of(v)];
+                let w = unfold[&(surface(v) + face.dir(dir) - face.n)];
+                let w_face = &faces[&face_of(w)];
+                let w_dir = [N, E, S, W]
+                    .into_iter()
+                    .find(|&d| w_face.dir(d) == -face.n)
+                    .unwrap();
+                res.insert((v, dir), (w, w_dir));
+            }
+        }
+    }
+    res
+}
+
+/// An axis-aligned box of lattice points, inclusive of `min` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Bounds<const D: usize> {
     pub min: SVector<i64, D>,
     pub max: SVector<i64, D>,
@@ -109,6 +375,141 @@ impl<const D: usize> Bounds<D> {
     pub fn contains(&self, v: SVector<i64, D>) -> bool {
         self.min.iter().zip(&v).all(|(a, b)| a <= b) && self.max.iter().zip(&v).all(|(a, b)| a >= b)
     }
+
+    /// The number of lattice points in the box.
+    pub fn volume(&self) -> i64 {
+        self.size().product()
+    }
+
+    /// Grows the box by `n` in every direction.
+    pub fn expand(&self, n: i64) -> Self {
+        Self {
+            min: self.min.add_scalar(-n),
+            max: self.max.add_scalar(n),
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let res = Self {
+            min: self.min.sup(&other.min),
+            max: self.max.inf(&other.max),
+        };
+        res.min
+            .iter()
+            .zip(&res.max)
+            .all(|(a, b)| a <= b)
+            .then_some(res)
+    }
+
+    /// Disjoint boxes covering every point which is in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        // Slice off the parts of self which are outside the overlap one axis at a time.
+        let mut res = Vec::new();
+        let mut rest = *self;
+        for axis in 0..D {
+            if rest.min[axis] < overlap.min[axis] {
+                let mut slice = rest;
+                slice.max[axis] = overlap.min[axis] - 1;
+                res.push(slice);
+                rest.min[axis] = overlap.min[axis];
+            }
+            if rest.max[axis] > overlap.max[axis] {
+                let mut slice = rest;
+                slice.min[axis] = overlap.max[axis] + 1;
+                res.push(slice);
+                rest.max[axis] = overlap.max[axis];
+            }
+        }
+        res
+    }
+
+    /// Disjoint boxes covering every point which is in either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Vec<Self> {
+        let mut res = vec![*self];
+        res.extend(other.difference(self));
+        res
+    }
+
+    /// Splits the box in half along every axis (into quadrants in 2D, octants in 3D). Axes of size 1
+    /// can't be split, so the result can have fewer than `2^D` boxes.
+    pub fn split(&self) -> Vec<Self> {
+        let mut res = vec![*self];
+        for axis in 0..D {
+            if self.min[axis] < self.max[axis] {
+                let mid = (self.min[axis] + self.max[axis]).div_euclid(2);
+                res = res
+                    .into_iter()
+                    .flat_map(|b| {
+                        let (mut low, mut high) = (b, b);
+                        low.max[axis] = mid;
+                        high.min[axis] = mid + 1;
+                        [low, high]
+                    })
+                    .collect();
+            }
+        }
+        res
+    }
+
+    /// Every lattice point in the box.
+    pub fn points(&self) -> impl Iterator<Item = SVector<i64, D>> + use<D> {
+        let bounds = *self;
+        let mut next = (0..D)
+            .all(|axis| bounds.min[axis] <= bounds.max[axis])
+            .then_some(bounds.min);
+        iter::from_fn(move || {
+            let res = next?;
+            next = None;
+            let mut v = res;
+            for axis in 0..D {
+                if v[axis] < bounds.max[axis] {
+                    v[axis] += 1;
+                    next = Some(v);
+                    break;
+                }
+                v[axis] = bounds.min[axis];
+            }
+            Some(res)
+        })
+    }
+}
+
+/// A set of lattice points, stored as a union of disjoint boxes.
+#[derive(Debug, Clone, Default)]
+pub struct BoxSet<const D: usize> {
+    boxes: Vec<Bounds<D>>,
+}
+
+impl<const D: usize> BoxSet<D> {
+    pub fn new() -> Self {
+        Self { boxes: Vec::new() }
+    }
+
+    pub fn add(&mut self, b: Bounds<D>) {
+        self.remove(b);
+        self.boxes.push(b);
+    }
+
+    /// Only the boxes which overlap `b` are split, the rest are left where they are.
+    pub fn remove(&mut self, b: Bounds<D>) {
+        let mut i = 0;
+        while i < self.boxes.len() {
+            if self.boxes[i].intersection(&b).is_some() {
+                let existing = self.boxes.swap_remove(i);
+                self.boxes.extend(existing.difference(&b));
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// The number of lattice points in the set.
+    pub fn volume(&self) -> i64 {
+        self.boxes.iter().map(Bounds::volume).sum()
+    }
 }
 
 impl<const D: usize> FromIterator<SVector<i64, D>> for Bounds<D> {
@@ -195,13 +596,46 @@ impl<T> Grid<T> {
                 f(v, c)
             })
             .collect();
-        assert!(
-            size.x * size.y == i64::try_from(data.len()).unwrap(),
-            "string is not rectangular",
-        );
+        if size.x * size.y != i64::try_from(data.len()).unwrap() {
+            let width = s.lines().next().map_or(0, |line| line.chars().count());
+            let (i, line) = s
+                .lines()
+                .enumerate()
+                .find(|(_, line)| line.chars().count() != width)
+                .expect("string is not rectangular");
+            panic!(
+                "string is not rectangular: line {} has length {}, but line 1 has length {width}",
+                i + 1,
+                line.chars().count(),
+            );
+        }
         Self::from_vec(size, data)
     }
 
+    /// Like `parse`, but also finds the positions of marker chars (e.g. start and end points), in
+    /// reading order. `marker` returns the char to parse in place of a marker (e.g. the floor
+    /// underneath it), or `None` for chars which aren't markers.
+    pub fn parse_markers<M, F>(
+        s: &str,
+        mut marker: M,
+        mut f: F,
+    ) -> (Self, HashMap<char, Vec<Vector>>)
+    where
+        M: FnMut(char) -> Option<char>,
+        F: FnMut(Vector, char) -> T,
+    {
+        let mut markers: HashMap<char, Vec<Vector>> = HashMap::new();
+        let grid = Self::parse(s, |v, c| {
+            if let Some(replacement) = marker(c) {
+                markers.entry(c).or_default().push(v);
+                f(v, replacement)
+            } else {
+                f(v, c)
+            }
+        });
+        (grid, markers)
+    }
+
     pub fn get<V: Into<Vector>>(&self, v: V) -> Option<&T> {
         let v: Vector = v.into();
         if 0 <= v.x && v.x < self.size.x && 0 <= v.y && v.y < self.size.y {
@@ -257,6 +691,106 @@ impl<T> Grid<T> {
     pub fn len(&self) -> usize {
         self.data.len()
     }
+
+    // Breadth first search from the sources, only stepping orthogonally on to cells where `passable`
+    // holds, yielding each reachable cell with its number of steps from the nearest source.
+    fn steps<I, P>(&self, sources: I, mut passable: P) -> impl Iterator<Item = (Vector, usize)>
+    where
+        I: IntoIterator<Item = Vector>,
+        P: FnMut(&T) -> bool,
+    {
+        // Start from a virtual cell which is adjacent to every source.
+        let sources: Vec<Vector> = sources.into_iter().collect();
+        let mut visit = search::grid_filter(self.size, |&v| v);
+        search::breadth_first(
+            (None, 0),
+            move |&(v, steps): &(Option<Vector>, usize), push| match v {
+                None => sources.iter().for_each(|&s| push((Some(s), 0))),
+                Some(v) => v
+                    .adjacent4()
+                    .filter(|&a| self.get(a).is_some_and(&mut passable))
+                    .for_each(|a| push((Some(a), steps + 1))),
+            },
+            move |&(v, _)| v.is_none_or(|v| visit(&v)),
+        )
+        .filter_map(|(v, steps)| Some((v?, steps)))
+    }
+
+    /// The number of orthogonal steps from the nearest source to each cell, only stepping on to cells
+    /// where `passable` holds. Unreachable cells are `None`.
+    pub fn distances<I, P>(&self, sources: I, passable: P) -> Grid<Option<usize>>
+    where
+        I: IntoIterator<Item = Vector>,
+        P: FnMut(&T) -> bool,
+    {
+        let mut res = Grid::new(None, self.size);
+        for (v, steps) in self.steps(sources, passable) {
+            res[v] = Some(steps);
+        }
+        res
+    }
+
+    /// Like `distances`, but stepping on to a cell costs `cost` of that cell, or is impossible if
+    /// `cost` is `None`.
+    pub fn weighted_distances<I, C, O>(&self, sources: I, mut cost: C) -> Grid<Option<O>>
+    where
+        I: IntoIterator<Item = Vector>,
+        C: FnMut(&T) -> Option<O>,
+        O: Zero + Ord + Copy,
+    {
+        let sources: Vec<Vector> = sources.into_iter().collect();
+        let mut res = Grid::new(None, self.size);
+        for (v, dist) in search::dijkstra_edges(None, |&v: &Option<Vector>, push| match v {
+            None => sources.iter().for_each(|&s| push(Some(s), O::zero())),
+            Some(v) => {
+                for a in v.adjacent4() {
+                    if let Some(cost) = self.get(a).and_then(&mut cost) {
+                        push(Some(a), cost);
+                    }
+                }
+            }
+        }) {
+            if let Some(v) = v {
+                res[v] = Some(dist);
+            }
+        }
+        res
+    }
+
+    /// A shortest path of orthogonal steps from start to goal, including both ends, only stepping on
+    /// to cells where `passable` holds.
+    pub fn shortest_path<P>(
+        &self,
+        start: Vector,
+        goal: Vector,
+        mut passable: P,
+    ) -> Option<Vec<Vector>>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let mut search = search::breadth_first_paths(
+            start,
+            |&v: &Vector, push| {
+                v.adjacent4()
+                    .filter(|&a| self.get(a).is_some_and(&mut passable))
+                    .for_each(push);
+            },
+            |&v| v,
+        );
+        let goal = search.find(|&v| v == goal)?;
+        Some(search.path(&goal).into_iter().copied().collect())
+    }
+
+    /// The cells at most `k` orthogonal steps from the start, only stepping on to cells where
+    /// `passable` holds.
+    pub fn within<P>(&self, start: Vector, k: usize, passable: P) -> impl Iterator<Item = Vector>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.steps([start], passable)
+            .take_while(move |&(_, steps)| steps <= k)
+            .map(|(v, _)| v)
+    }
 }
 
 impl<T, V> Index<V> for Grid<T>
@@ -308,3 +842,381 @@ impl Grid<bool> {
         self.keys().filter(|&v| self[v])
     }
 }
+
+/// A dense grid in any number of dimensions, covering `bounds` (which needn't start at the origin).
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GridN<T, const D: usize> {
+    data: Vec<T>,
+    pub bounds: Bounds<D>,
+}
+
+impl<T, const D: usize> GridN<T, D> {
+    pub fn new(init: T, bounds: Bounds<D>) -> Self
+    where
+        T: Clone,
+    {
+        let data = vec![init; usize::try_from(bounds.volume()).unwrap()];
+        Self { data, bounds }
+    }
+
+    pub fn from_fn<F: FnMut(SVector<i64, D>) -> T>(bounds: Bounds<D>, f: F) -> Self {
+        Self {
+            data: bounds.points().map(f).collect(),
+            bounds,
+        }
+    }
+
+    // Cells are stored in the order of `Bounds::points`, with the first axis varying fastest.
+    fn index(&self, v: SVector<i64, D>) -> Option<usize> {
+        self.bounds.contains(v).then(|| {
+            let size = self.bounds.size();
+            let i = (0..D).rev().fold(0, |i, axis| {
+                i * size[axis] + v[axis] - self.bounds.min[axis]
+            });
+            usize::try_from(i).unwrap()
+        })
+    }
+
+    pub fn get(&self, v: SVector<i64, D>) -> Option<&T> {
+        self.index(v).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, v: SVector<i64, D>) -> Option<&mut T> {
+        self.index(v).map(|i| &mut self.data[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SVector<i64, D>, &T)> {
+        self.bounds.points().zip(&self.data)
+    }
+
+    /// The orthogonal neighbours of `v` which are in the grid.
+    pub fn adjacent(
+        &self,
+        v: SVector<i64, D>,
+    ) -> impl Iterator<Item = SVector<i64, D>> + use<T, D> {
+        let bounds = self.bounds;
+        (0..D)
+            .flat_map(move |axis| {
+                [-1, 1].into_iter().map(move |step| {
+                    let mut w = v;
+                    w[axis] += step;
+                    w
+                })
+            })
+            .filter(move |&w| bounds.contains(w))
+    }
+
+    /// The lower dimensional grid through `at` which spans the given axes. For example
+    /// `slice([0, 1], at)` is the xy plane of a 3D grid at height `at.z`.
+    pub fn slice<const E: usize>(&self, axes: [usize; E], at: SVector<i64, D>) -> GridN<T, E>
+    where
+        T: Clone,
+    {
+        let bounds = Bounds {
+            min: SVector::from_fn(|i, _| self.bounds.min[axes[i]]),
+            max: SVector::from_fn(|i, _| self.bounds.max[axes[i]]),
+        };
+        GridN::from_fn(bounds, |u| {
+            let mut v = at;
+            for (i, &axis) in axes.iter().enumerate() {
+                v[axis] = u[i];
+            }
+            self[v].clone()
+        })
+    }
+}
+
+impl<T, const D: usize> Index<SVector<i64, D>> for GridN<T, D> {
+    type Output = T;
+
+    fn index(&self, v: SVector<i64, D>) -> &Self::Output {
+        self.get(v).unwrap()
+    }
+}
+
+impl<T, const D: usize> IndexMut<SVector<i64, D>> for GridN<T, D> {
+    fn index_mut(&mut self, v: SVector<i64, D>) -> &mut Self::Output {
+        self.get_mut(v).unwrap()
+    }
+}
+
+/// Unlike `Grid<bool>`, the points keep their coordinates, and the grid covers exactly their bounds.
+impl<I, const D: usize> From<I> for GridN<bool, D>
+where
+    I: IntoIterator<Item = SVector<i64, D>> + Clone,
+{
+    fn from(points: I) -> Self {
+        let mut res = Self::new(false, points.clone().into_iter().collect());
+        for point in points {
+            res[point] = true;
+        }
+        res
+    }
+}
+
+/// A table for summing the cells of a grid over any box in constant time.
+/// <https://en.wikipedia.org/wiki/Summed-area_table>
+pub struct SummedArea<T> {
+    // The sum of every cell above and to the left of each position, so one bigger than the grid.
+    sums: Grid<T>,
+}
+
+impl<T: Num + Copy> SummedArea<T> {
+    pub fn new(grid: &Grid<T>) -> Self {
+        let mut sums = Grid::new(T::zero(), grid.size + SE);
+        for (v, &cell) in grid {
+            sums[v + SE] = cell + sums[v + S] + sums[v + E] - sums[v];
+        }
+        Self { sums }
+    }
+
+    /// The sum of the cells in `bounds`, which must be within the grid.
+    pub fn sum(&self, bounds: Bounds<2>) -> T {
+        let (a, b) = (bounds.min, bounds.max + SE);
+        self.sums[b] + self.sums[a] - self.sums[[a.x, b.y]] - self.sums[[b.x, a.y]]
+    }
+}
+
+/// A 4-connected region of a grid, as found by `Grid::regions` or `Grid::regions_where`.
+#[derive(Debug, Clone, Copy)]
+pub struct Region {
+    pub area: usize,
+    pub perimeter: usize,
+    /// The number of straight sides making up the perimeter (including the perimeters of holes).
+    pub sides: usize,
+    pub bounds: Bounds<2>,
+    /// The number of 4-connected areas outside the region which the region completely surrounds.
+    pub holes: usize,
+}
+
+impl<T> Grid<T> {
+    /// Breaks the grid into 4-connected regions of equal cells. Returns the index of the region each
+    /// cell belongs to, along with the regions themselves.
+    pub fn regions(&self) -> (Grid<Option<usize>>, Vec<Region>)
+    where
+        T: PartialEq,
+    {
+        self.regions_by(|_| true, PartialEq::eq)
+    }
+
+    /// Breaks the cells where `pred` holds into 4-connected regions. Returns the index of the region
+    /// each cell belongs to (if any), along with the regions themselves.
+    pub fn regions_where<P>(&self, mut pred: P) -> (Grid<Option<usize>>, Vec<Region>)
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.regions_by(&mut pred, |_, _| true)
+    }
+
+    fn regions_by<P, F>(&self, mut pred: P, mut same: F) -> (Grid<Option<usize>>, Vec<Region>)
+    where
+        P: FnMut(&T) -> bool,
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut labels = Grid::new(None, self.size);
+        let mut cells = Vec::new();
+        for start in self.keys() {
+            if labels[start].is_some() || !pred(&self[start]) {
+                continue;
+            }
+            let label = cells.len();
+            labels[start] = Some(label);
+            let mut region = vec![start];
+            let mut stack = vec![start];
+            while let Some(v) = stack.pop() {
+                for u in v.adjacent4() {
+                    if self.get(u).is_some_and(|c| same(&self[v], c))
+                        && labels[u].is_none()
+                        && pred(&self[u])
+                    {
+                        labels[u] = Some(label);
+                        region.push(u);
+                        stack.push(u);
+                    }
+                }
+            }
+            cells.push(region);
+        }
+
+        let regions = cells
+            .iter()
+            .enumerate()
+            .map(|(label, cells)| {
+                let contains = |v: Vector| labels.get(v) == Some(&Some(label));
+                // An edge of the region is a cell in the region together with a direction in which
+                // the neighbouring cell is outside the region. An edge starts a new side unless the
+                // cell to its left has the same edge.
+                let edges = || {
+                    cells
+                        .iter()
+                        .flat_map(|&v| [N, E, S, W].map(|dir| (v, dir)))
+                        .filter(|&(v, dir)| !contains(v + dir))
+                };
+                let bounds: Bounds<2> = cells.iter().copied().collect();
+                Region {
+                    area: cells.len(),
+                    perimeter: edges().count(),
+                    sides: edges()
+                        .filter(|&(v, dir)| {
+                            let left = v + LEFT * dir;
+                            !contains(left) || contains(left + dir)
+                        })
+                        .count(),
+                    bounds,
+                    holes: holes(bounds, contains),
+                }
+            })
+            .collect();
+
+        (labels, regions)
+    }
+}
+
+// Counts the 4-connected areas within the bounds which are outside the region, but which can't
+// reach the outside of the bounds without crossing the region.
+fn holes<F: Fn(Vector) -> bool>(bounds: Bounds<2>, contains: F) -> usize {
+    let bounds = bounds.expand(1);
+    let mut visited = Grid::new(false, bounds.size());
+    let mut areas = 0;
+    for start in visited.keys() {
+        if visited[start] || contains(start + bounds.min) {
+            continue;
+        }
+        areas += 1;
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            for u in v.adjacent4() {
+                if visited.get(u) == Some(&false) && !contains(u + bounds.min) {
+                    visited[u] = true;
+                    stack.push(u);
+                }
+            }
+        }
+    }
+    // The first area we find contains the corner of the bounds, so it's outside the region.
+    areas - 1
+}
+
+/// A simple polygon with its vertices on the lattice.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Vector>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vector>) -> Self {
+        Self { vertices }
+    }
+
+    /// Builds a polygon by starting at the origin and walking each of the given distances in the
+    /// given directions, which should eventually bring us back to the origin.
+    pub fn from_moves<I: IntoIterator<Item = (Vector, i64)>>(moves: I) -> Self {
+        let mut pos = Z;
+        let mut vertices = vec![pos];
+        for (dir, len) in moves {
+            pos += dir * len;
+            vertices.push(pos);
+        }
+        assert_eq!(vertices.pop(), Some(Z), "moves don't form a loop");
+        Self::new(vertices)
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Vector, Vector)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(&a, &b)| (a, b))
+    }
+
+    /// Twice the area, so that it's always an integer. Positive if the vertices run clockwise (with
+    /// y increasing downwards as usual), negative if they run anticlockwise.
+    ///
+    /// <https://en.wikipedia.org/wiki/Shoelace_formula>
+    pub fn double_signed_area(&self) -> i64 {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    /// The number of lattice points on the boundary.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| num::integer::gcd((b - a).x, (b - a).y))
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside the polygon.
+    ///
+    /// <https://en.wikipedia.org/wiki/Pick%27s_theorem>
+    pub fn interior_points(&self) -> i64 {
+        (self.double_signed_area().abs() - self.boundary_points()) / 2 + 1
+    }
+
+    /// Whether the point is inside the polygon or on its boundary.
+    pub fn contains(&self, v: Vector) -> bool {
+        self.contains_doubled(v * 2)
+    }
+
+    // As contains, but takes a point with its coordinates doubled, so that we can ask about points
+    // half way between lattice points.
+    fn contains_doubled(&self, v: Vector) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (a * 2, b * 2);
+            let (ab, av) = (b - a, v - a);
+            if ab.perp(&av) == 0 && ab.dot(&av) >= 0 && ab.dot(&av) <= ab.dot(&ab) {
+                return true;
+            }
+            // Cast a ray from v in the positive x direction and count the edges it crosses.
+            if (a.y > v.y) != (b.y > v.y) && (av.x * ab.y < av.y * ab.x) == (ab.y > 0) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Whether the rectangle with the given opposite corners is entirely inside the polygon (allowing
+    /// for the rectangle to touch the boundary). The polygon must be rectilinear.
+    pub fn contains_rect(&self, a: Vector, b: Vector) -> bool {
+        let min = a.inf(&b);
+        let max = a.sup(&b);
+        if min.x == max.x || min.y == max.y {
+            return self.contains_segment(min, max);
+        }
+        // If no edge passes through the inside of the rectangle, then either the whole rectangle is
+        // inside the polygon or the whole rectangle is outside, so checking the centre is enough.
+        self.edges().all(|(p, q)| {
+            assert!(p.x == q.x || p.y == q.y, "polygon is not rectilinear");
+            let (p, q) = (p.inf(&q), p.sup(&q));
+            !(min.x < q.x && p.x < max.x && min.y < q.y && p.y < max.y)
+        }) && self.contains_doubled(min + max)
+    }
+
+    // Whether the horizontal or vertical segment from a to b (with a <= b) is inside the polygon. The
+    // segment can only pass from inside to outside where it meets an edge, so it's enough to check
+    // every point where it meets an edge and every point half way between.
+    fn contains_segment(&self, a: Vector, b: Vector) -> bool {
+        let (axis, other) = if a.y == b.y { (0, 1) } else { (1, 0) };
+        let mut events = vec![a[axis], b[axis]];
+        for (p, q) in self.edges() {
+            let (p, q) = (p.inf(&q), p.sup(&q));
+            if p[other] <= a[other] && a[other] <= q[other] {
+                events.extend(
+                    [p[axis], q[axis]]
+                        .into_iter()
+                        .filter(|&x| a[axis] < x && x < b[axis]),
+                );
+            }
+        }
+        events.sort_unstable();
+        events.dedup();
+        events.iter().all(|&x| {
+            let mut point = a;
+            point[axis] = x;
+            self.contains(point)
+        }) && events.windows(2).all(|pair| {
+            let mut point = a * 2;
+            point[axis] = pair[0] + pair[1];
+            self.contains_doubled(point)
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use crate::grid::Grid;
+
+/// A pixel of a binary PPM (colour) or PGM (greyscale) image. Most image viewers and ffmpeg
+/// understand these formats, and they're simple enough to write by hand.
+pub trait Pixel: Copy {
+    const MAGIC: &'static str;
+    const EXTENSION: &'static str;
+
+    fn extend(self, buf: &mut Vec<u8>);
+}
+
+/// Greyscale, from black at 0 to white at 255.
+impl Pixel for u8 {
+    const MAGIC: &'static str = "P5";
+    const EXTENSION: &'static str = "pgm";
+
+    fn extend(self, buf: &mut Vec<u8>) {
+        buf.push(self);
+    }
+}
+
+/// Red, green, blue.
+impl Pixel for [u8; 3] {
+    const MAGIC: &'static str = "P6";
+    const EXTENSION: &'static str = "ppm";
+
+    fn extend(self, buf: &mut Vec<u8>) {
+        buf.extend(self);
+    }
+}
+
+/// Writes the grid with each cell coloured by `palette` and drawn as a `scale` by `scale` square.
+/// Sparse point sets can be written by first converting them with `Grid::<bool>::from`.
+pub fn write<W, T, P, F>(mut w: W, grid: &Grid<T>, scale: usize, mut palette: F) -> io::Result<()>
+where
+    W: Write,
+    P: Pixel,
+    F: FnMut(&T) -> P,
+{
+    let width = usize::try_from(grid.size.x).unwrap() * scale;
+    let height = usize::try_from(grid.size.y).unwrap() * scale;
+    write!(w, "{}\n{width} {height}\n255\n", P::MAGIC)?;
+    let mut row = Vec::new();
+    for y in 0..grid.size.y {
+        row.clear();
+        for x in 0..grid.size.x {
+            let pixel = palette(&grid[[x, y]]);
+            for _ in 0..scale {
+                pixel.extend(&mut row);
+            }
+        }
+        for _ in 0..scale {
+            w.write_all(&row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes numbered frames of a simulation to `$FRAMES/<name>/`, if the `FRAMES` environment variable
+/// is set, and does nothing otherwise. Assemble the frames with e.g.
+/// `ffmpeg -i $FRAMES/<name>/%06d.ppm out.gif`.
+pub struct Recorder {
+    dir: Option<PathBuf>,
+    scale: usize,
+    frame: usize,
+}
+
+impl Recorder {
+    pub fn from_env(name: &str, scale: usize) -> Self {
+        let dir = env::var_os("FRAMES").map(|root| PathBuf::from(root).join(name));
+        if let Some(dir) = &dir {
+            fs::create_dir_all(dir).unwrap();
+        }
+        Self {
+            dir,
+            scale,
+            frame: 0,
+        }
+    }
+
+    /// Check this before building a grid which only exists to be recorded.
+    pub fn is_recording(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    pub fn record<T, P, F>(&mut self, grid: &Grid<T>, palette: F)
+    where
+        P: Pixel,
+        F: FnMut(&T) -> P,
+    {
+        if let Some(dir) = &self.dir {
+            let path = dir.join(format!("{:06}.{}", self.frame, P::EXTENSION));
+            write(
+                BufWriter::new(File::create(path).unwrap()),
+                grid,
+                self.scale,
+                palette,
+            )
+            .unwrap();
+            self.frame += 1;
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+/// A set of integers, stored as a sorted list of disjoint inclusive ranges, no two of which touch.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IntervalSet<N> {
+    ranges: Vec<(N, N)>,
+}
+
+impl<N: PrimInt> IntervalSet<N> {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<N>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        // Merge with every range which overlaps or touches the new one.
+        let i = self
+            .ranges
+            .partition_point(|&(_, e)| e.saturating_add(N::one()) < start);
+        let mut j = i;
+        while j < self.ranges.len() && self.ranges[j].0 <= end.saturating_add(N::one()) {
+            start = start.min(self.ranges[j].0);
+            end = end.max(self.ranges[j].1);
+            j += 1;
+        }
+        self.ranges.splice(i..j, [(start, end)]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<N>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        let j = self.ranges.partition_point(|&(s, _)| s <= end);
+        let mut remaining = Vec::new();
+        if i < j {
+            if self.ranges[i].0 < start {
+                remaining.push((self.ranges[i].0, start - N::one()));
+            }
+            if self.ranges[j - 1].1 > end {
+                remaining.push((end + N::one(), self.ranges[j - 1].1));
+            }
+        }
+        self.ranges.splice(i..j, remaining);
+    }
+
+    pub fn contains(&self, n: N) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < n);
+        i < self.ranges.len() && self.ranges[i].0 <= n
+    }
+
+    /// The number of integers in the set. This can be more than `N` can hold (e.g. every `u32`), so
+    /// it's counted in a `u128`, which only overflows for a set of every `u128` or every `i128`.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(s, e)| match (s.to_i128(), e.to_i128()) {
+                (Some(s), Some(e)) => e.abs_diff(s) + 1,
+                _ => (e - s).to_u128().unwrap() + 1,
+            })
+            .sum()
+    }
+
+    /// The disjoint ranges making up the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<N>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        for range in other.iter() {
+            res.insert(range);
+        }
+        res
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        for range in other.iter() {
+            res.remove(range);
+        }
+        res
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.difference(&self.difference(other))
+    }
+
+    /// Everything within the given bounds which isn't in the set, e.g. for iterating over the gaps.
+    pub fn complement(&self, bounds: RangeInclusive<N>) -> Self {
+        Self::from_iter([bounds]).difference(self)
+    }
+}
+
+impl<N: PrimInt> FromIterator<RangeInclusive<N>> for IntervalSet<N> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<N>>>(ranges: I) -> Self {
+        let mut res = Self::new();
+        for range in ranges {
+            res.insert(range);
+        }
+        res
+    }
+}
+
+/// A function on integers which shifts each of a set of disjoint ranges on to another range of the
+/// same length, and leaves everything outside those ranges where it is.
+#[derive(Clone, Debug, Default)]
+pub struct RangeMap<N> {
+    // Sorted, disjoint (src_start, src_end, dst_start) triples.
+    pieces: Vec<(N, N, N)>,
+}
+
+impl<N: PrimInt> RangeMap<N> {
+    pub fn new() -> Self {
+        Self { pieces: Vec::new() }
+    }
+
+    /// Maps `src` on to the range of the same length starting at `dst`. `src` must be disjoint from
+    /// the ranges already in the map.
+    pub fn insert(&mut self, src: RangeInclusive<N>, dst: N) {
+        let (start, end) = src.into_inner();
+        if start > end {
+            return;
+        }
+        let i = self.pieces.partition_point(|&(_, e, _)| e < start);
+        assert!(
+            i == self.pieces.len() || end < self.pieces[i].0,
+            "overlapping ranges",
+        );
+        self.pieces.insert(i, (start, end, dst));
+    }
+
+    pub fn get(&self, n: N) -> N {
+        let i = self.pieces.partition_point(|&(_, e, _)| e < n);
+        match self.pieces.get(i) {
+            Some(&(s, _, d)) if s <= n => d + (n - s),
+            _ => n,
+        }
+    }
+
+    /// Maps every integer in the set.
+    pub fn apply(&self, set: &IntervalSet<N>) -> IntervalSet<N> {
+        self.pieces
+            .iter()
+            .fold(set.difference(&self.domain()), |res, &(s, e, d)| {
+                let moved = set
+                    .intersection(&IntervalSet::from_iter([s..=e]))
+                    .iter()
+                    .map(|range| d + (*range.start() - s)..=d + (*range.end() - s))
+                    .collect();
+                res.union(&moved)
+            })
+    }
+
+    /// The map which applies `self` and then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut res = Self::new();
+        for &(s, e, d) in &self.pieces {
+            for (other_s, other_e, other_d) in other.split(d, d + (e - s)) {
+                res.insert(s + (other_s - d)..=s + (other_e - d), other_d);
+            }
+        }
+        let domain = self.domain();
+        for &(s, e, d) in &other.pieces {
+            for range in IntervalSet::from_iter([s..=e]).difference(&domain).iter() {
+                res.insert(range.clone(), d + (*range.start() - s));
+            }
+        }
+        res
+    }
+
+    fn domain(&self) -> IntervalSet<N> {
+        self.pieces.iter().map(|&(s, e, _)| s..=e).collect()
+    }
+
+    // Splits the range from start to end into pieces, each of which is shifted as a whole. Returns
+    // (src_start, src_end, dst_start) for each piece.
+    fn split(&self, start: N, end: N) -> Vec<(N, N, N)> {
+        let mut res = Vec::new();
+        let mut pos = start;
+        let i = self.pieces.partition_point(|&(_, e, _)| e < start);
+        for &(s, e, d) in &self.pieces[i..] {
+            if s > end {
+                break;
+            }
+            if pos < s {
+                res.push((pos, s - N::one(), pos));
+            }
+            let low = s.max(pos);
+            let high = e.min(end);
+            res.push((low, high, d + (low - s)));
+            if high == end {
+                return res;
+            }
+            pos = high + N::one();
+        }
+        res.push((pos, end, pos));
+        res
+    }
+}
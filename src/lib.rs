@@ -12,12 +12,20 @@ use std::{fs, path::Path};
 pub mod intcode;
 pub mod solutions;
 
+mod automaton;
+mod bit_grid;
 mod cast;
 mod combinatorics;
+mod cycle;
 mod freqs;
+mod graph;
 mod grid;
 mod hash;
+mod image;
+mod interval_set;
 mod lex_ord;
+mod max_queue;
+mod memo;
 mod number_theory;
 mod ocr;
 mod part;
@@ -27,6 +35,8 @@ mod unbounded_vec;
 mod uniq;
 mod vm_2018;
 
+pub use search::{record_stats, take_stats};
+
 fn get(path: &str) -> Result<String, String> {
     let res = match reqwest::blocking::Client::new()
         .get(format!("https://adventofcode.com/{path}"))
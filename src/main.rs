@@ -1,9 +1,10 @@
 use std::{
     collections::BTreeMap,
+    env,
     time::{Duration, Instant},
 };
 
-use advent_of_code::{get_answer, get_input, solutions};
+use advent_of_code::{get_answer, get_input, record_stats, solutions, take_stats};
 
 fn sig_figs(n: u32, duration: Duration) -> Duration {
     let nanos = u64::try_from(duration.as_nanos()).unwrap();
@@ -15,6 +16,12 @@ fn sig_figs(n: u32, duration: Duration) -> Duration {
 }
 
 fn run_part(year: u16, day: u8, part: u8, f: fn(&str) -> String, input: &str) -> (Duration, usize) {
+    // Set SEARCH_STATS to e.g. 2022/19 to see what the searches in that day got up to.
+    let stats = env::var("SEARCH_STATS").is_ok_and(|chosen| chosen == format!("{year}/{day:0>2}"));
+    if stats {
+        record_stats();
+    }
+
     let now = Instant::now();
     let answer = f(input);
     let elapsed = now.elapsed();
@@ -36,6 +43,10 @@ fn run_part(year: u16, day: u8, part: u8, f: fn(&str) -> String, input: &str) ->
         if stars == 0 { "   ?" } else { "" },
     );
 
+    if stats && let Some(stats) = take_stats() {
+        println!("             {stats}");
+    }
+
     (elapsed, stars)
 }
 
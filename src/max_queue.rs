@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+
+/// A first in first out queue which can also find its largest element in amortised constant time,
+/// for finding the maxima of sliding windows. Wrap elements in `Reverse` to find minima instead.
+pub struct MaxQueue<T> {
+    // Elements which are at least as large as everything pushed after them, along with their
+    // positions in the sequence of pushes. Decreasing, so the front is the max.
+    candidates: VecDeque<(usize, T)>,
+    pushed: usize,
+    popped: usize,
+}
+
+impl<T: Ord> MaxQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            candidates: VecDeque::new(),
+            pushed: 0,
+            popped: 0,
+        }
+    }
+
+    pub fn push(&mut self, x: T) {
+        while self.candidates.back().is_some_and(|(_, y)| *y < x) {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.pushed, x));
+        self.pushed += 1;
+    }
+
+    /// Removes the element which was pushed first.
+    pub fn pop(&mut self) {
+        assert!(self.popped < self.pushed, "queue is empty");
+        if self
+            .candidates
+            .front()
+            .is_some_and(|&(i, _)| i == self.popped)
+        {
+            self.candidates.pop_front();
+        }
+        self.popped += 1;
+    }
+
+    /// The largest element and its position in the sequence of pushes (counting from 0). Ties go to
+    /// the element which was pushed first.
+    pub fn max(&self) -> Option<(usize, &T)> {
+        self.candidates.front().map(|(i, x)| (*i, x))
+    }
+}
@@ -0,0 +1,55 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Remembers the result of `f` for every argument it's called with. `f` is passed a handle which it
+/// can recurse through, so that recursive calls hit the cache too.
+pub struct Memo<A, R, F> {
+    cache: HashMap<A, R>,
+    hits: usize,
+    f: F,
+}
+
+impl<A, R, F> Memo<A, R, F>
+where
+    A: Eq + Hash + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            f,
+        }
+    }
+
+    pub fn get(&mut self, arg: A) -> R {
+        get(&self.f, &mut self.cache, &mut self.hits, arg)
+    }
+}
+
+impl<A, R, F> Memo<A, R, F> {
+    /// The number of results in the cache.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// The number of calls, recursive or not, which were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+}
+
+fn get<A, R, F>(f: &F, cache: &mut HashMap<A, R>, hits: &mut usize, arg: A) -> R
+where
+    A: Eq + Hash + Clone,
+    R: Clone,
+    F: Fn(&mut dyn FnMut(A) -> R, A) -> R,
+{
+    if let Some(res) = cache.get(&arg) {
+        *hits += 1;
+        return res.clone();
+    }
+    let res = f(&mut |arg| get(f, cache, hits, arg), arg.clone());
+    cache.insert(arg, res.clone());
+    res
+}
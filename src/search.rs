@@ -1,11 +1,130 @@
 use std::{
-    cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashSet, VecDeque},
+    cell::RefCell,
+    cmp::{self, Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque, hash_map::Entry},
+    fmt,
     hash::Hash,
+    iter, mem,
     ops::Add,
+    time::{Duration, Instant},
 };
 
-use num::PrimInt;
+use num::{PrimInt, Zero};
+
+use crate::grid::{self, Grid, Vector};
+
+/// What the search drivers got up to, for tuning pruning and heuristics. States are filtered when
+/// they're popped but rejected by the filter (or already visited, for drivers which track that
+/// themselves). Elapsed time only counts time spent inside the drivers, callbacks included. Results
+/// cached and cache hits are totalled over every `Memo`.
+#[derive(Clone, Default)]
+pub struct Stats {
+    pub pushed: usize,
+    pub popped: usize,
+    pub filtered: usize,
+    pub max_frontier: usize,
+    pub elapsed: Duration,
+    pub cached: usize,
+    pub hits: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "pushed {}, popped {}, filtered {}, max frontier {}, elapsed {:?}, cached {}, hits {}",
+            self.pushed,
+            self.popped,
+            self.filtered,
+            self.max_frontier,
+            self.elapsed,
+            self.cached,
+            self.hits,
+        )
+    }
+}
+
+thread_local! {
+    static STATS: RefCell<Option<Stats>> = const { RefCell::new(None) };
+}
+
+/// Starts totting up stats for every search on this thread, until `take_stats` is called.
+pub fn record_stats() {
+    STATS.set(Some(Stats::default()));
+}
+
+/// Stops recording and returns the stats for every search since `record_stats` which has run out of
+/// states or been dropped. Searches which are still running aren't counted.
+pub fn take_stats() -> Option<Stats> {
+    STATS.take()
+}
+
+/// Adds the size of a cache, and how many times it was hit, to the totals if we're recording.
+pub fn record_cache(cached: usize, hits: usize) {
+    STATS.with_borrow_mut(|total| {
+        if let Some(total) = total {
+            total.cached += cached;
+            total.hits += hits;
+        }
+    });
+}
+
+// Stats for a single search, which are added to the totals when the search runs out of states or is
+// dropped, whichever comes first. Counting is cheap enough to do regardless, but we only look at the
+// clock while recording.
+struct Probe {
+    recording: bool,
+    stats: Stats,
+}
+
+impl Probe {
+    fn new() -> Self {
+        Self {
+            recording: STATS.with_borrow(Option::is_some),
+            stats: Stats::default(),
+        }
+    }
+
+    fn push(&mut self) {
+        self.stats.pushed += 1;
+        self.stats.max_frontier = self
+            .stats
+            .max_frontier
+            .max(self.stats.pushed - self.stats.popped);
+    }
+
+    fn time<T, F: FnOnce(&mut Self) -> Option<T>>(&mut self, f: F) -> Option<T> {
+        let now = self.recording.then(Instant::now);
+        let res = f(self);
+        if let Some(now) = now {
+            self.stats.elapsed += now.elapsed();
+        }
+        if res.is_none() {
+            self.finish();
+        }
+        res
+    }
+
+    fn finish(&mut self) {
+        if mem::take(&mut self.recording) {
+            STATS.with_borrow_mut(|total| {
+                if let Some(total) = total {
+                    total.pushed += self.stats.pushed;
+                    total.popped += self.stats.popped;
+                    total.filtered += self.stats.filtered;
+                    total.max_frontier = total.max_frontier.max(self.stats.max_frontier);
+                    total.elapsed += self.stats.elapsed;
+                }
+            });
+        }
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
 
 trait Queue {
     type Item;
@@ -27,6 +146,19 @@ impl<T> Queue for VecDeque<T> {
     }
 }
 
+/// Last-in-first-out stack.
+impl<T> Queue for Vec<T> {
+    type Item = T;
+
+    fn push(&mut self, value: Self::Item) {
+        Vec::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<Self::Item> {
+        Vec::pop(self)
+    }
+}
+
 struct CostValue<V, O> {
     cost: O,
     value: V,
@@ -52,7 +184,7 @@ impl<V, O: Ord> Ord for CostValue<V, O> {
     }
 }
 
-struct CostHeap<V, C, O> {
+pub struct CostHeap<V, C, O> {
     cost: C,
     binary_heap: BinaryHeap<Reverse<CostValue<V, O>>>,
 }
@@ -100,6 +232,58 @@ pub fn id_filter<S: Clone + Eq + Hash>() -> impl FnMut(&S) -> bool {
     hash_filter(Clone::clone)
 }
 
+/// Like `hash_filter`, for keys which are points in a grid of the given size, so that we can track
+/// them in a `Grid` rather than a `HashSet`. Also prunes keys outside the grid.
+pub fn grid_filter<S, H>(size: Vector, mut key: H) -> impl FnMut(&S) -> bool
+where
+    H: FnMut(&S) -> Vector,
+{
+    let mut visited = Grid::new(false, size);
+    move |state| {
+        visited
+            .get_mut(key(state))
+            .is_some_and(|visited| !mem::replace(visited, true))
+    }
+}
+
+/// Like `grid_filter`, for keys which are a point and an orthogonal direction. Panics on any other
+/// direction.
+pub fn grid_dir_filter<S, H>(size: Vector, mut key: H) -> impl FnMut(&S) -> bool
+where
+    H: FnMut(&S) -> (Vector, Vector),
+{
+    let mut visited = Grid::new([false; 4], size);
+    move |state| {
+        let (pos, dir) = key(state);
+        let i = [grid::N, grid::E, grid::S, grid::W]
+            .iter()
+            .position(|&d| d == dir)
+            .unwrap();
+        visited
+            .get_mut(pos)
+            .is_some_and(|visited| !mem::replace(&mut visited[i], true))
+    }
+}
+
+/// Like `hash_filter`, for keys which are small integers, so that we can track them in a bitset.
+pub fn index_filter<S, H>(mut key: H) -> impl FnMut(&S) -> bool
+where
+    H: FnMut(&S) -> usize,
+{
+    let mut visited: Vec<u64> = Vec::new();
+    move |state| {
+        let i = key(state);
+        if i / 64 >= visited.len() {
+            visited.resize(i / 64 + 1, 0);
+        }
+        let word = &mut visited[i / 64];
+        let bit = 1 << (i % 64);
+        let unvisited = *word & bit == 0;
+        *word |= bit;
+        unvisited
+    }
+}
+
 /// Doesn't prune the search space at all.
 pub fn no_filter<S>(_: &S) -> bool {
     true
@@ -116,15 +300,24 @@ where
     A: FnMut(&S, &mut dyn FnMut(S)),
     F: FnMut(&S) -> bool,
 {
+    let mut probe = Probe::new();
     queue.push(start);
-    std::iter::from_fn(move || {
-        while let Some(state) = queue.pop() {
-            if filter(&state) {
-                adjacent(&state, &mut |a| queue.push(a));
-                return Some(state);
+    probe.push();
+    iter::from_fn(move || {
+        probe.time(|probe| {
+            while let Some(state) = queue.pop() {
+                probe.stats.popped += 1;
+                if filter(&state) {
+                    adjacent(&state, &mut |a| {
+                        probe.push();
+                        queue.push(a);
+                    });
+                    return Some(state);
+                }
+                probe.stats.filtered += 1;
             }
-        }
-        None
+            None
+        })
     })
 }
 
@@ -137,6 +330,94 @@ where
     search(VecDeque::new(), start, adjacent, filter)
 }
 
+/// Search a state space depth first.
+pub fn depth_first<S, A, F>(start: S, adjacent: A, filter: F) -> impl Iterator<Item = S>
+where
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    F: FnMut(&S) -> bool,
+{
+    search(Vec::new(), start, adjacent, filter)
+}
+
+/// Search a state space depth first, never going deeper than a limit which starts at zero and goes
+/// up by one every time the search runs out of states, and return a shallowest goal state. Since
+/// the search restarts each time, it takes a function which makes a fresh filter for every pass.
+/// The goal is only a shallowest one if the filter is `no_filter` or keys on something which
+/// includes the depth: otherwise a pass can reach a state deep first and then reject its shallow
+/// route, and miss a goal under it.
+pub fn iterative_deepening<S, A, N, F, G>(
+    start: S,
+    mut adjacent: A,
+    mut new_filter: N,
+    mut goal: G,
+) -> Option<S>
+where
+    S: Clone,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    N: FnMut() -> F,
+    F: FnMut(&S) -> bool,
+    G: FnMut(&S) -> bool,
+{
+    ida_star(
+        (start, 0),
+        |(state, depth): &(S, usize), push| adjacent(state, &mut |a| push((a, depth + 1))),
+        || {
+            let mut filter = new_filter();
+            move |(state, _): &(S, usize)| filter(state)
+        },
+        |(state, _)| goal(state),
+        |&(_, depth)| depth,
+        |_| 0,
+    )
+    .map(|(state, _)| state)
+}
+
+/// Search a state space depth first, never exceeding a limit on cost-plus-heuristic which starts at
+/// that of the start state and goes up just far enough to admit a new state every time the search
+/// runs out of states, and return a lowest cost goal state. Like `a_star`, but only using memory for
+/// the current branch (plus whatever the filter needs) at the expense of repeated work. Since the
+/// search restarts each time, it takes a function which makes a fresh filter for every pass. As
+/// with `iterative_deepening`, the goal is only a lowest cost one if the filter is `no_filter` or
+/// keys on something which includes the cost.
+pub fn ida_star<S, A, N, F, G, C, D, O>(
+    start: S,
+    mut adjacent: A,
+    mut new_filter: N,
+    mut goal: G,
+    mut cost: C,
+    mut heuristic: D,
+) -> Option<S>
+where
+    S: Clone,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    N: FnMut() -> F,
+    F: FnMut(&S) -> bool,
+    G: FnMut(&S) -> bool,
+    C: FnMut(&S) -> O,
+    D: FnMut(&S) -> O,
+    O: Add<Output = O> + Ord + Copy,
+{
+    let mut limit = cost(&start) + heuristic(&start);
+    loop {
+        let mut filter = new_filter();
+        let mut next_limit = None;
+        if let Some(state) = depth_first(start.clone(), &mut adjacent, |state| {
+            let estimate = cost(state) + heuristic(state);
+            if estimate > limit {
+                next_limit = Some(next_limit.map_or(estimate, |l| cmp::min(l, estimate)));
+                false
+            } else {
+                filter(state)
+            }
+        })
+        .find(|state| goal(state))
+        {
+            return Some(state);
+        }
+        limit = next_limit?;
+    }
+}
+
 /// Search a state space min-cost first.
 pub fn dijkstra<S, A, F, C, O>(start: S, adjacent: A, filter: F, cost: C) -> impl Iterator<Item = S>
 where
@@ -156,6 +437,47 @@ where
     )
 }
 
+/// Search a graph min-cost first, where `adjacent` gives each adjacent state along with the cost of
+/// the edge to it, so that states don't need to carry their accumulated cost. Distances are tracked
+/// by state, so a state is only pushed again if it's cheaper than last time, and stale entries are
+/// skipped. Yields each reachable state once with its lowest cost, so collecting the whole search
+/// gives a distance table from the start.
+pub fn dijkstra_edges<S, A, O>(start: S, mut adjacent: A) -> impl Iterator<Item = (S, O)>
+where
+    S: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S, O)),
+    O: Zero + Ord + Copy,
+{
+    let mut dists = HashMap::from([(start.clone(), O::zero())]);
+    let mut heap = BinaryHeap::from([Reverse(CostValue {
+        cost: O::zero(),
+        value: start,
+    })]);
+    let mut probe = Probe::new();
+    probe.push();
+    iter::from_fn(move || {
+        probe.time(|probe| {
+            while let Some(Reverse(CostValue { cost, value: state })) = heap.pop() {
+                probe.stats.popped += 1;
+                if cost > dists[&state] {
+                    probe.stats.filtered += 1;
+                    continue;
+                }
+                adjacent(&state, &mut |a, edge| {
+                    let cost = cost + edge;
+                    if dists.get(&a).is_none_or(|&dist| cost < dist) {
+                        dists.insert(a.clone(), cost);
+                        probe.push();
+                        heap.push(Reverse(CostValue { cost, value: a }));
+                    }
+                });
+                return Some((state, cost));
+            }
+            None
+        })
+    })
+}
+
 /// Search a state space min-cost-plus-heuristic first.
 pub fn a_star<S, A, F, C, D, O>(
     start: S,
@@ -177,6 +499,419 @@ where
     })
 }
 
+// A state along with the hash key of the state it was reached from, if any.
+type Step<S, K> = (S, Option<K>);
+
+/// A search which remembers how it reached each state, so that paths can be reconstructed without
+/// storing them in the states themselves. States are pruned by hash key as with `hash_filter`.
+pub struct PathSearch<Q, S, K, A, H> {
+    queue: Q,
+    adjacent: A,
+    hash_key: H,
+    // For each visited key, the state that was yielded and the key of the state it was reached from.
+    visited: HashMap<K, Step<S, K>>,
+    probe: Probe,
+}
+
+impl<Q, S, K, A, H> PathSearch<Q, S, K, A, H>
+where
+    H: FnMut(&S) -> K,
+    K: Clone + Eq + Hash,
+{
+    /// The path from the start to the given state, which must already have been yielded, including
+    /// both ends. The number of steps taken is one less than the length of the path.
+    pub fn path(&mut self, state: &S) -> Vec<&S> {
+        let mut res = Vec::new();
+        let mut key = (self.hash_key)(state);
+        loop {
+            let (state, parent) = &self.visited[&key];
+            res.push(state);
+            let Some(parent) = parent else { break };
+            key.clone_from(parent);
+        }
+        res.reverse();
+        res
+    }
+}
+
+impl<Q, S, K, A, H> Iterator for PathSearch<Q, S, K, A, H>
+where
+    Q: Queue<Item = Step<S, K>>,
+    S: Clone,
+    K: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        self.probe.time(|probe| {
+            while let Some((state, parent)) = self.queue.pop() {
+                probe.stats.popped += 1;
+                let key = (self.hash_key)(&state);
+                if let Entry::Vacant(entry) = self.visited.entry(key.clone()) {
+                    entry.insert((state.clone(), parent));
+                    let queue = &mut self.queue;
+                    (self.adjacent)(&state, &mut |a| {
+                        probe.push();
+                        queue.push((a, Some(key.clone())));
+                    });
+                    return Some(state);
+                }
+                probe.stats.filtered += 1;
+            }
+            None
+        })
+    }
+}
+
+/// Like `breadth_first` with a `hash_filter`, but recording paths as it goes.
+pub fn breadth_first_paths<S, K, A, H>(
+    start: S,
+    adjacent: A,
+    hash_key: H,
+) -> PathSearch<VecDeque<Step<S, K>>, S, K, A, H>
+where
+    S: Clone,
+    K: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+{
+    let mut queue = VecDeque::new();
+    queue.push_back((start, None));
+    let mut probe = Probe::new();
+    probe.push();
+    PathSearch {
+        queue,
+        adjacent,
+        hash_key,
+        visited: HashMap::new(),
+        probe,
+    }
+}
+
+/// Like `dijkstra` with a `hash_filter`, but recording paths as it goes. A state's predecessor is
+/// only recorded once the state is popped, so every path is a lowest cost one.
+#[expect(clippy::type_complexity)]
+pub fn dijkstra_paths<S, K, A, H, C, O>(
+    start: S,
+    adjacent: A,
+    hash_key: H,
+    mut cost: C,
+) -> PathSearch<CostHeap<Step<S, K>, impl FnMut(&Step<S, K>) -> O, O>, S, K, A, H>
+where
+    S: Clone,
+    K: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+    C: FnMut(&S) -> O,
+    O: Ord,
+{
+    let mut queue = CostHeap {
+        cost: move |(state, _): &Step<S, K>| cost(state),
+        binary_heap: BinaryHeap::new(),
+    };
+    queue.push((start, None));
+    let mut probe = Probe::new();
+    probe.push();
+    PathSearch {
+        queue,
+        adjacent,
+        hash_key,
+        visited: HashMap::new(),
+        probe,
+    }
+}
+
+/// Like `a_star` with a `hash_filter`, but recording paths as it goes, as with `dijkstra_paths`.
+#[allow(dead_code)]
+#[expect(clippy::type_complexity)]
+pub fn a_star_paths<S, K, A, H, C, D, O>(
+    start: S,
+    adjacent: A,
+    hash_key: H,
+    mut cost: C,
+    mut heuristic: D,
+) -> PathSearch<CostHeap<Step<S, K>, impl FnMut(&Step<S, K>) -> O::Output, O::Output>, S, K, A, H>
+where
+    S: Clone,
+    K: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+    C: FnMut(&S) -> O,
+    D: FnMut(&S) -> O,
+    O: Add,
+    O::Output: Ord,
+{
+    dijkstra_paths(start, adjacent, hash_key, move |state| {
+        cost(state) + heuristic(state)
+    })
+}
+
+struct Node<S, K, O> {
+    state: S,
+    cost: O,
+    parents: Vec<K>,
+    count: usize,
+}
+
+/// A min-cost first search which keeps track of every lowest cost way of reaching each state, rather
+/// than just the first. States are identified by hash key, and costs must strictly increase with
+/// every step.
+pub struct ShortestPaths<S, K, O, A, H, C> {
+    heap: BinaryHeap<Reverse<CostValue<Step<S, K>, O>>>,
+    adjacent: A,
+    hash_key: H,
+    cost: C,
+    visited: HashMap<K, Node<S, K, O>>,
+    // States we've reached, but which we can't yield until everything of the same cost has been
+    // popped, since there might be other equally cheap ways to reach them.
+    pending: VecDeque<(O, S)>,
+    probe: Probe,
+}
+
+impl<S, K, O, A, H, C> ShortestPaths<S, K, O, A, H, C>
+where
+    K: Clone + Eq + Hash,
+    H: FnMut(&S) -> K,
+{
+    /// The number of distinct lowest cost paths from the start to the given state, which must
+    /// already have been yielded.
+    pub fn path_count(&mut self, state: &S) -> usize {
+        self.visited[&(self.hash_key)(state)].count
+    }
+
+    /// Every state which lies on at least one lowest cost path from the start to the given state,
+    /// which must already have been yielded.
+    pub fn on_paths(&mut self, state: &S) -> Vec<&S> {
+        let key = (self.hash_key)(state);
+        let mut seen = HashSet::new();
+        let mut res = Vec::new();
+        let mut stack = vec![key];
+        while let Some(key) = stack.pop() {
+            if seen.insert(key.clone()) {
+                let node = &self.visited[&key];
+                res.push(&node.state);
+                stack.extend(node.parents.iter().cloned());
+            }
+        }
+        res
+    }
+}
+
+impl<S, K, O, A, H, C> Iterator for ShortestPaths<S, K, O, A, H, C>
+where
+    S: Clone,
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+    C: FnMut(&S) -> O,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        self.probe.time(|probe| {
+            loop {
+                if let Some((cost, _)) = self.pending.front()
+                    && self.heap.peek().is_none_or(|next| next.0.cost > *cost)
+                {
+                    return self.pending.pop_front().map(|(_, state)| state);
+                }
+                let CostValue {
+                    cost,
+                    value: (state, parent),
+                } = self.heap.pop()?.0;
+                probe.stats.popped += 1;
+                let key = (self.hash_key)(&state);
+                let count = parent
+                    .as_ref()
+                    .map_or(1, |parent| self.visited[parent].count);
+                match self.visited.entry(key.clone()) {
+                    Entry::Occupied(mut entry) => {
+                        let node = entry.get_mut();
+                        if node.cost == cost {
+                            node.parents.extend(parent);
+                            node.count += count;
+                        } else {
+                            probe.stats.filtered += 1;
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(Node {
+                            state: state.clone(),
+                            cost: cost.clone(),
+                            parents: parent.into_iter().collect(),
+                            count,
+                        });
+                        let heap = &mut self.heap;
+                        let cost_fn = &mut self.cost;
+                        (self.adjacent)(&state, &mut |a| {
+                            probe.push();
+                            heap.push(Reverse(CostValue {
+                                cost: cost_fn(&a),
+                                value: (a, Some(key.clone())),
+                            }));
+                        });
+                        self.pending.push_back((cost, state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Like `dijkstra` with a `hash_filter`, but yielding each state only once every lowest cost path
+/// to it is known.
+pub fn shortest_paths<S, K, O, A, H, C>(
+    start: S,
+    adjacent: A,
+    hash_key: H,
+    mut cost: C,
+) -> ShortestPaths<S, K, O, A, H, C>
+where
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+    C: FnMut(&S) -> O,
+    O: Ord,
+{
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse(CostValue {
+        cost: cost(&start),
+        value: (start, None),
+    }));
+    let mut probe = Probe::new();
+    probe.push();
+    ShortestPaths {
+        heap,
+        adjacent,
+        hash_key,
+        cost,
+        visited: HashMap::new(),
+        pending: VecDeque::new(),
+        probe,
+    }
+}
+
+// One half of a bidirectional search. For each visited key, the state, the key of the state it was
+// reached from, and the number of steps from the root.
+struct Side<S, K> {
+    frontier: Vec<S>,
+    visited: HashMap<K, (S, Option<K>, usize)>,
+    depth: usize,
+}
+
+impl<S: Clone, K: Clone + Eq + Hash> Side<S, K> {
+    fn new(root: S, key: K) -> Self {
+        Self {
+            frontier: vec![root.clone()],
+            visited: HashMap::from([(key, (root, None, 0))]),
+            depth: 0,
+        }
+    }
+
+    // Visits the next level, returning the key where this side meets the other, if it does.
+    fn expand<A, H>(
+        &mut self,
+        other: &Self,
+        adjacent: &mut A,
+        hash_key: &mut H,
+        probe: &mut Probe,
+    ) -> Option<K>
+    where
+        A: FnMut(&S, &mut dyn FnMut(S)),
+        H: FnMut(&S) -> K,
+    {
+        // Finish the whole level even once the sides have met, since a later meeting in the same
+        // level might be closer to the other side's root.
+        self.depth += 1;
+        let mut frontier = Vec::new();
+        let mut meeting: Option<(usize, K)> = None;
+        for state in mem::take(&mut self.frontier) {
+            probe.stats.popped += 1;
+            let key = hash_key(&state);
+            adjacent(&state, &mut |a| {
+                let a_key = hash_key(&a);
+                if let Entry::Vacant(entry) = self.visited.entry(a_key.clone()) {
+                    if let Some(&(_, _, depth)) = other.visited.get(&a_key)
+                        && meeting.as_ref().is_none_or(|&(best, _)| depth < best)
+                    {
+                        meeting = Some((depth, a_key));
+                    }
+                    entry.insert((a.clone(), Some(key.clone()), self.depth));
+                    probe.push();
+                    frontier.push(a);
+                } else {
+                    probe.stats.filtered += 1;
+                }
+            });
+        }
+        self.frontier = frontier;
+        meeting.map(|(_, key)| key)
+    }
+
+    // The states from the given key back to the root.
+    fn trace(&self, mut key: K) -> Vec<S> {
+        let mut res = Vec::new();
+        loop {
+            let (state, parent, _) = &self.visited[&key];
+            res.push(state.clone());
+            let Some(parent) = parent else { break };
+            key.clone_from(parent);
+        }
+        res
+    }
+}
+
+/// Search a state space breadth first from the start and the goal at once, a level at a time from
+/// whichever side has the smaller frontier, until the two meet in the middle. `backward` gives the
+/// states from which a given state is adjacent. Returns a shortest path from start to goal,
+/// including both ends, or `None` if there isn't one. The number of steps taken is one less than
+/// the length of the path.
+pub fn bidirectional<S, K, A, B, H>(
+    start: S,
+    goal: S,
+    mut forward: A,
+    mut backward: B,
+    mut hash_key: H,
+) -> Option<Vec<S>>
+where
+    S: Clone,
+    K: Clone + Eq + Hash,
+    A: FnMut(&S, &mut dyn FnMut(S)),
+    B: FnMut(&S, &mut dyn FnMut(S)),
+    H: FnMut(&S) -> K,
+{
+    let start_key = hash_key(&start);
+    let goal_key = hash_key(&goal);
+    if start_key == goal_key {
+        return Some(vec![start]);
+    }
+    let mut forwards = Side::new(start, start_key);
+    let mut backwards = Side::new(goal, goal_key);
+    let mut probe = Probe::new();
+    probe.push();
+    probe.push();
+    probe.time(|probe| {
+        loop {
+            if forwards.frontier.is_empty() || backwards.frontier.is_empty() {
+                return None;
+            }
+            let meeting = if forwards.frontier.len() <= backwards.frontier.len() {
+                forwards.expand(&backwards, &mut forward, &mut hash_key, probe)
+            } else {
+                backwards.expand(&forwards, &mut backward, &mut hash_key, probe)
+            };
+            if let Some(key) = meeting {
+                let mut path = forwards.trace(key.clone());
+                path.reverse();
+                path.extend(backwards.trace(key).into_iter().skip(1));
+                return Some(path);
+            }
+        }
+    })
+}
+
 /// Search a state space for a lowest cost solution given: a cost function to evaluate a given
 /// state, and a bound function to give a lower bound on the cost of the best state reachable from a
 /// given state.
@@ -1,40 +1,26 @@
-use crate::{
-    grid::{Adjacent, Grid},
-    part::Part,
-};
+use crate::{bit_grid::BitGrid, grid::Grid, part::Part};
 
-fn step(lights: &mut Grid<bool>, scrap: &mut Grid<bool>) {
-    for (pos, light) in &*lights {
-        let neighbors_on = pos
-            .adjacent8()
-            .filter(|&v| lights.get(v).is_some_and(|&p| p))
-            .count();
-        scrap[pos] = neighbors_on == 3 || *light && neighbors_on == 2;
-    }
-    std::mem::swap(lights, scrap);
-}
-
-fn fix_corners(lights: &mut Grid<bool>) {
+fn fix_corners(lights: &mut BitGrid) {
     for x in [0, lights.size.x - 1] {
         for y in [0, lights.size.y - 1] {
-            lights[[x, y]] = true;
+            lights.insert([x, y]);
         }
     }
 }
 
 fn part_(part: Part, steps: u32, input: &str) -> usize {
-    let mut lights = Grid::parse(input, |_, c| c == '#');
-    let mut scrap = Grid::new(false, lights.size);
+    let mut lights = BitGrid::from(&Grid::parse(input, |_, c| c == '#'));
     if part == Part::Two {
         fix_corners(&mut lights);
     }
     for _ in 0..steps {
-        step(&mut lights, &mut scrap);
+        let counts = lights.neighbour_counts();
+        lights = &counts[3] | &(&lights & &counts[2]);
         if part == Part::Two {
             fix_corners(&mut lights);
         }
     }
-    lights.into_values().filter(|light| *light).count()
+    lights.count_ones()
 }
 
 pub fn part1(input: &str) -> usize {
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::grid::{N, Turn, Z};
+use crate::grid::{Metric, N, Turn, Z};
 
 fn parse(input: &str) -> impl Iterator<Item = (Turn, i64)> + '_ {
     input.split(", ").map(|instruction| {
@@ -31,7 +31,7 @@ pub fn part2(input: &str) -> i64 {
         for _ in 0..dist {
             pos += dir;
             if visited.contains(&pos) {
-                return pos.abs().sum();
+                return pos.manhattan();
             }
             visited.insert(pos);
         }
@@ -1,5 +1,5 @@
 use crate::{
-    grid::{Adjacent, Vector},
+    grid::{Adjacent, Grid, Vector},
     search,
 };
 
@@ -47,8 +47,14 @@ pub fn part1(input: &str) -> u32 {
     part1_(Vector::new(31, 39), input)
 }
 
+// In 50 steps we can't get further than (51, 51).
 pub fn part2(input: &str) -> usize {
-    search(input).take_while(|state| state.steps <= 50).count()
+    let seed = input.parse().unwrap();
+    let mut office = Grid::new(false, [52, 52]);
+    for v in office.keys() {
+        office[v] = is_open(seed, v);
+    }
+    office.within(Vector::new(1, 1), 50, |&open| open).count()
 }
 
 pub fn tests() {
@@ -1,10 +1,12 @@
 use md5::{Digest, Md5};
 
 use crate::{
-    grid::{Vector, Z},
+    grid::{Metric, Vector, Z},
     search,
 };
 
+const VAULT: Vector = Vector::new(3, 3);
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 struct State {
     path: String,
@@ -12,7 +14,7 @@ struct State {
 }
 
 fn adjacent(input: &str, state: &State, push: &mut dyn FnMut(State)) {
-    if state.pos == Vector::new(3, 3) {
+    if is_vault(state) {
         return;
     }
 
@@ -47,30 +49,40 @@ fn adjacent(input: &str, state: &State, push: &mut dyn FnMut(State)) {
     }
 }
 
+fn start() -> State {
+    State {
+        path: String::new(),
+        pos: Z,
+    }
+}
+
+fn is_vault(state: &State) -> bool {
+    state.pos == VAULT
+}
+
+// The path is part of the state, so every state is distinct and there's nothing to filter, which
+// `ida_star` relies on to find a shortest path. Each step moves one square, so the distance to the
+// vault never overestimates.
 pub fn part1(input: &str) -> String {
-    search::breadth_first(
-        State {
-            path: String::new(),
-            pos: Z,
-        },
+    search::ida_star(
+        start(),
         |state, push| adjacent(input, state, push),
-        search::id_filter(),
+        || search::no_filter,
+        is_vault,
+        |state| state.path.len(),
+        |state| usize::try_from((VAULT - state.pos).manhattan()).unwrap(),
     )
-    .find(|state| state.pos == Vector::new(3, 3))
     .unwrap()
     .path
 }
 
 pub fn part2(input: &str) -> usize {
-    search::breadth_first(
-        State {
-            path: String::new(),
-            pos: Z,
-        },
+    search::depth_first(
+        start(),
         |state, push| adjacent(input, state, push),
-        search::id_filter(),
+        search::no_filter,
     )
-    .filter(|state| state.pos == Vector::new(3, 3))
+    .filter(is_vault)
     .map(|state| state.path.len())
     .max()
     .unwrap()
@@ -81,6 +93,20 @@ pub fn tests() {
     assert_eq!(part1("kglvqrro"), "DDUDRLRRUDRD");
     assert_eq!(part1("ulqzkmiv"), "DRURDRUDDLLDLUURRDULRLDUUDDDRR");
 
+    // Without the heuristic we try every path up to the same length, and find the same one. Again
+    // there's nothing to filter, so it's a shortest one.
+    assert_eq!(
+        search::iterative_deepening(
+            start(),
+            |state, push| adjacent("ihgpwlah", state, push),
+            || search::no_filter,
+            is_vault,
+        )
+        .unwrap()
+        .path,
+        "DDRRRD",
+    );
+
     assert_eq!(part2("ihgpwlah"), 370);
     assert_eq!(part2("kglvqrro"), 492);
     assert_eq!(part2("ulqzkmiv"), 830);
@@ -1,45 +1,26 @@
-use std::cmp;
+use crate::interval_set::IntervalSet;
 
-fn parse(input: &str) -> Vec<(u32, u32)> {
+fn parse(input: &str) -> IntervalSet<u32> {
     input
         .lines()
         .map(|line| {
             let (low, high) = line.split_once('-').unwrap();
-            (low.parse().unwrap(), high.parse().unwrap())
+            low.parse().unwrap()..=high.parse().unwrap()
         })
         .collect()
 }
 
 pub fn part1(input: &str) -> u32 {
-    let mut blacklist = parse(input);
-    blacklist.sort_unstable();
-
-    let mut lowest = 0;
-    for (low, high) in blacklist {
-        if lowest < low {
-            return lowest;
-        }
-        lowest = high + 1;
-    }
-    unreachable!()
+    *parse(input)
+        .complement(0..=u32::MAX)
+        .iter()
+        .next()
+        .unwrap()
+        .start()
 }
 
 fn part2_(max: u32, input: &str) -> u32 {
-    let mut blacklist = parse(input);
-    blacklist.sort_unstable();
-
-    let mut lowest = 0;
-    let mut count = 0;
-    for (low, high) in blacklist {
-        if lowest < low {
-            count += low - lowest;
-        }
-        if high == max {
-            return count;
-        }
-        lowest = cmp::max(lowest, high + 1);
-    }
-    max - lowest + 1 + count
+    u32::try_from(parse(input).complement(0..=max).len()).unwrap()
 }
 
 pub fn part2(input: &str) -> u32 {
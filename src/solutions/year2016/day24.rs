@@ -1,66 +1,30 @@
-use std::{
-    cmp,
-    collections::{HashMap, HashSet},
-};
+use std::{cmp, collections::HashMap};
 
 use crate::{
     combinatorics::permute,
-    grid::{Adjacent, Grid, Vector},
-    search,
+    grid::{Grid, Vector},
 };
 
-fn distance(ducts: &HashSet<Vector>, a: Vector, b: Vector) -> u32 {
-    struct State {
-        pos: Vector,
-        steps: u32,
-    }
-    search::breadth_first(
-        State { pos: a, steps: 0 },
-        |state, push| {
-            for pos in state.pos.adjacent4() {
-                if ducts.contains(&pos) {
-                    push(State {
-                        pos,
-                        steps: state.steps + 1,
-                    });
-                }
-            }
-        },
-        search::hash_filter(|state: &State| state.pos),
-    )
-    .find(|state| state.pos == b)
-    .unwrap()
-    .steps
-}
-
-fn total_dist(dists: &HashMap<(char, char), u32>, route: &[char]) -> u32 {
+fn total_dist(dists: &HashMap<(char, char), usize>, route: &[char]) -> usize {
     route
         .windows(2)
         .map(|pair| dists[&(pair[0], pair[1])])
         .sum()
 }
 
-fn part_(return_to_start: bool, input: &str) -> u32 {
-    let map = Grid::parse(input, |_, c| c);
-
-    let nodes: Vec<(Vector, char)> = map
-        .iter()
-        .filter(|(_, c)| c.is_ascii_digit())
-        .map(|(p, c)| (p, *c))
-        .collect();
-
-    let ducts: HashSet<Vector> = map
-        .iter()
-        .filter(|(_, c)| c.is_ascii_digit() || **c == '.')
-        .map(|(p, _)| p)
-        .collect();
-
-    let mut dists: HashMap<(char, char), u32> = HashMap::new();
-    for (i, &(a_p, a)) in nodes.iter().enumerate() {
-        for &(b_p, b) in &nodes[i + 1..] {
-            let dist = distance(&ducts, a_p, b_p);
-            dists.insert((a, b), dist);
-            dists.insert((b, a), dist);
+fn part_(return_to_start: bool, input: &str) -> usize {
+    let (map, markers) = Grid::parse_markers(
+        input,
+        |c| c.is_ascii_digit().then_some('.'),
+        |_, c| c == '.',
+    );
+    let nodes: Vec<(Vector, char)> = markers.into_iter().map(|(c, ps)| (ps[0], c)).collect();
+
+    let mut dists: HashMap<(char, char), usize> = HashMap::new();
+    for &(a_p, a) in &nodes {
+        let from_a = map.distances([a_p], |&open| open);
+        for &(b_p, b) in &nodes {
+            dists.insert((a, b), from_a[b_p].unwrap());
         }
     }
 
@@ -80,11 +44,11 @@ fn part_(return_to_start: bool, input: &str) -> u32 {
     best_total_dist
 }
 
-pub fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> usize {
     part_(false, input)
 }
 
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> usize {
     part_(true, input)
 }
 
@@ -1,6 +1,4 @@
-use std::collections::HashMap;
-
-use crate::part::Part;
+use crate::{cycle::Cycle, part::Part};
 
 fn redistribute(banks: &mut [u8]) {
     // max_by_key is last wins, and we want first wins, so rev
@@ -15,24 +13,14 @@ fn redistribute(banks: &mut [u8]) {
 }
 
 fn part_(part: Part, input: &str) -> usize {
-    let mut banks = input
+    let banks: Vec<u8> = input
         .split_whitespace()
         .map(|w| w.parse().unwrap())
-        .collect::<Vec<_>>();
-
-    let mut seen = HashMap::new();
-    let mut cycle = 0;
-
-    loop {
-        seen.insert(banks.clone(), cycle);
-        redistribute(&mut banks);
-        cycle += 1;
-        if let Some(prev) = seen.get(&banks) {
-            return match part {
-                Part::One => cycle,
-                Part::Two => cycle - prev,
-            };
-        }
+        .collect();
+    let cycle = Cycle::find(banks, |banks| redistribute(banks), Clone::clone);
+    match part {
+        Part::One => cycle.start + cycle.len,
+        Part::Two => cycle.len,
     }
 }
 
@@ -18,7 +18,7 @@ fn search(graph: &HashMap<u32, Vec<u32>>, start: u32) -> impl Iterator<Item = u3
     search::breadth_first(
         start,
         |&pos, push| graph[&pos].iter().copied().for_each(push),
-        search::id_filter(),
+        search::index_filter(|&pos| usize::try_from(pos).unwrap()),
     )
 }
 
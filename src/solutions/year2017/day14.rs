@@ -1,9 +1,4 @@
-use std::collections::HashSet;
-
-use crate::{
-    grid::{Adjacent, Grid},
-    hash, search,
-};
+use crate::{grid::Grid, hash};
 
 fn disk(input: &str) -> Grid<bool> {
     let mut res = Grid::new(false, [128, 128]);
@@ -23,27 +18,7 @@ pub fn part1(input: &str) -> usize {
 }
 
 pub fn part2(input: &str) -> usize {
-    let disk = disk(input);
-
-    let mut regions = 0;
-    let mut unexplored: HashSet<_> = disk.keys().filter(|&pos| disk[pos]).collect();
-
-    while !unexplored.is_empty() {
-        regions += 1;
-        for pos in search::breadth_first(
-            *unexplored.iter().next().unwrap(),
-            |&pos, push| {
-                pos.adjacent4()
-                    .filter(|&v| disk.get(v).is_some_and(|&used| used))
-                    .for_each(push);
-            },
-            search::id_filter(),
-        ) {
-            unexplored.remove(&pos);
-        }
-    }
-
-    regions
+    disk(input).regions_where(|&used| used).1.len()
 }
 
 pub fn tests() {
@@ -1,3 +1,7 @@
+use std::mem;
+
+use crate::cycle::Cycle;
+
 enum Step {
     Spin(usize),
     Exchange(usize, usize),
@@ -43,20 +47,12 @@ pub fn part1(input: &str) -> String {
 
 pub fn part2(input: &str) -> String {
     let steps = parse(input);
-
-    let initial = Vec::from("abcdefghijklmnop");
-    let mut progs = dance(&steps, initial.clone());
-    let mut period = 1;
-    while progs != initial {
-        progs = dance(&steps, progs);
-        period += 1;
-    }
-
-    for _ in 0..(1_000_000_000 % period) {
-        progs = dance(&steps, progs);
-    }
-
-    String::from_utf8(progs).unwrap()
+    let cycle = Cycle::find(
+        Vec::from("abcdefghijklmnop"),
+        |progs| *progs = dance(&steps, mem::take(progs)),
+        Clone::clone,
+    );
+    String::from_utf8(cycle.get(1_000_000_000).clone()).unwrap()
 }
 
 pub fn tests() {
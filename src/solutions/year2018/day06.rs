@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 
-use crate::grid::{Bounds, Vector};
+use crate::grid::{Bounds, Metric, Vector};
 
 fn unique_closest(pos: Vector, coordinates: &[Vector]) -> Option<Vector> {
-    let mut min_dist = (pos - coordinates[0]).abs().sum();
+    let mut min_dist = (pos - coordinates[0]).manhattan();
     let mut res = Some(coordinates[0]);
     for &c in &coordinates[1..] {
-        let dist = (pos - c).abs().sum();
+        let dist = (pos - c).manhattan();
         match dist.cmp(&min_dist) {
             std::cmp::Ordering::Less => {
                 min_dist = dist;
@@ -50,33 +50,25 @@ pub fn part1(input: &str) -> u32 {
     areas.into_values().max().unwrap()
 }
 
-fn part2_(tolerance: i64, input: &str) -> u32 {
+fn part2_(tolerance: i64, input: &str) -> usize {
     let coordinates = input
         .lines()
         .map(crate::cast::str_to_vector)
         .collect::<Vec<_>>();
 
-    let mut res = 0;
-
-    let bounds = Bounds::<2>::from(&coordinates);
-    for x in bounds.min.x..=bounds.max.x {
-        for y in bounds.min.y..=bounds.max.y {
-            let pos = Vector::new(x, y);
-            if coordinates
+    Bounds::<2>::from(&coordinates)
+        .points()
+        .filter(|&pos| {
+            coordinates
                 .iter()
-                .map(|&c| (pos - c).abs().sum())
+                .map(|&c| (pos - c).manhattan())
                 .sum::<i64>()
                 < tolerance
-            {
-                res += 1;
-            }
-        }
-    }
-
-    res
+        })
+        .count()
 }
 
-pub fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> usize {
     part2_(10_000, input)
 }
 
@@ -1,5 +1,5 @@
 use crate::{
-    grid::{Grid, NW, Vector},
+    grid::{Bounds, Grid, SE, SummedArea, Vector},
     part::Part,
 };
 
@@ -7,36 +7,32 @@ fn cell(serial: i64, pos: Vector) -> i64 {
     ((pos.x + 10) * pos.y + serial) * (pos.x + 10) / 100 % 10 - 5
 }
 
-// https://en.wikipedia.org/wiki/Summed-area_table
-fn summed_area_table(serial: i64) -> Grid<i64> {
-    let mut res = Grid::new(0, [301, 301]);
-    for x in 1..=300 {
-        for y in 1..=300 {
-            res[[x, y]] = cell(serial, Vector::new(x, y)) + res[[x - 1, y]] + res[[x, y - 1]]
-                - res[[x - 1, y - 1]];
-        }
+fn power_levels(serial: i64) -> SummedArea<i64> {
+    // Cells are numbered from 1, so the cell at pos is at pos - SE in the grid.
+    let mut grid = Grid::new(0, [300, 300]);
+    for pos in grid.keys() {
+        grid[pos] = cell(serial, pos + SE);
     }
-    res
-}
-
-fn square(sat: &Grid<i64>, pos: Vector, size: i64) -> i64 {
-    let base = pos + NW;
-    sat[base] + sat[base + Vector::new(size, size)]
-        - sat[base + Vector::new(size, 0)]
-        - sat[base + Vector::new(0, size)]
+    SummedArea::new(&grid)
 }
 
 fn part_(part: Part, input: &str) -> (Vector, i64) {
-    let sat = summed_area_table(input.parse().unwrap());
-    (match part {
+    let power = power_levels(input.parse().unwrap());
+    let (min, size) = (match part {
         Part::One => 3..=3,
         Part::Two => 1..=300,
     })
     .flat_map(|size| {
-        (1..=301 - size).flat_map(move |x| (1..=301 - size).map(move |y| (Vector::new(x, y), size)))
+        (0..=300 - size).flat_map(move |x| (0..=300 - size).map(move |y| (Vector::new(x, y), size)))
+    })
+    .max_by_key(|&(min, size)| {
+        power.sum(Bounds {
+            min,
+            max: min + Vector::new(size - 1, size - 1),
+        })
     })
-    .max_by_key(|&(pos, size)| square(&sat, pos, size))
-    .unwrap()
+    .unwrap();
+    (min + SE, size)
 }
 
 pub fn part1(input: &str) -> String {
@@ -1,5 +1,6 @@
 use crate::{
     grid::{self, Adjacent, Grid, Vector},
+    image::Recorder,
     part::Part,
     search,
 };
@@ -113,7 +114,7 @@ fn first_step(cave: &mut Grid<Square>, pos: Vector, target_kind: Kind) -> Option
                 }
             }
         },
-        search::hash_filter(|state: &State| state.pos),
+        search::grid_filter(cave.size, |state: &State| state.pos),
         // The cost function is fiddly, here's everything it needs to cover:
         // - To move, the unit first considers the squares that are in range and determines which of
         //   those squares it could reach in the fewest steps.
@@ -159,9 +160,26 @@ fn turn(part: Part, cave: &mut Grid<Square>, mut pos: Vector, target_kind: Kind)
     Ok(())
 }
 
+// Walls are grey, and units fade from bright green (elves) or red (goblins) as they lose hp.
+fn colour(square: &Square) -> [u8; 3] {
+    match square {
+        Square::Wall => [64, 64, 64],
+        Square::Empty => [0, 0, 0],
+        Square::Occupied(unit) => {
+            let bright = u8::try_from(55 + unit.hp.clamp(0, 200)).unwrap();
+            match unit.kind {
+                Kind::Elf => [0, bright, 0],
+                Kind::Goblin => [bright, 0, 0],
+            }
+        }
+    }
+}
+
 fn part_(part: Part, elf_ap: i32, input: &str) -> Result<i32> {
     let mut cave = parse(elf_ap, input);
+    let mut frames = Recorder::from_env(&format!("2018-15-{elf_ap}"), 8);
     for round in 0.. {
+        frames.record(&cave, colour);
         for pos in cave.keys() {
             if let Square::Occupied(unit) = &mut cave[pos] {
                 unit.moved = false;
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
-
-use crate::grid::{Adjacent, Grid};
+use crate::{
+    automaton::{Automaton, Dense},
+    grid::{Adjacent, Grid},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Tile {
@@ -18,64 +19,44 @@ fn parse(input: &str) -> Grid<Tile> {
     })
 }
 
-fn tick(state: &Grid<Tile>) -> Grid<Tile> {
-    let mut res = state.clone();
-    for pos in state.keys() {
-        let count_adj = |tile| {
-            pos.adjacent8()
-                .filter(|&v| state.get(v).is_some_and(|&t| t == tile))
-                .count()
-        };
-        match state[pos] {
-            Tile::Open => {
-                if count_adj(Tile::Trees) >= 3 {
-                    res[pos] = Tile::Trees;
-                }
-            }
-            Tile::Trees => {
-                if count_adj(Tile::Lumberyard) >= 3 {
-                    res[pos] = Tile::Lumberyard;
-                }
-            }
-            Tile::Lumberyard => {
-                if count_adj(Tile::Lumberyard) < 1 || count_adj(Tile::Trees) < 1 {
-                    res[pos] = Tile::Open;
-                }
-            }
+fn tick(tile: Tile, adjacent: &mut dyn Iterator<Item = &Tile>) -> Tile {
+    let (mut trees, mut lumberyards) = (0, 0);
+    for &t in adjacent {
+        match t {
+            Tile::Open => {}
+            Tile::Trees => trees += 1,
+            Tile::Lumberyard => lumberyards += 1,
         }
     }
-    res
-}
-
-fn find_cycle(mut state: Grid<Tile>) -> (usize, usize) {
-    let mut seen = HashMap::new();
-    let mut min = 0;
-    while !seen.contains_key(&state) {
-        let next_state = tick(&state);
-        seen.insert(state, min);
-        state = next_state;
-        min += 1;
+    match tile {
+        Tile::Open if trees >= 3 => Tile::Trees,
+        Tile::Trees if lumberyards >= 3 => Tile::Lumberyard,
+        Tile::Lumberyard if lumberyards < 1 || trees < 1 => Tile::Open,
+        _ => tile,
     }
-    (seen[&state], min)
 }
 
-fn part_(mins: usize, mut state: Grid<Tile>) -> usize {
-    for _ in 0..mins {
-        state = tick(&state);
-    }
+fn resource_value(state: &Grid<Tile>) -> usize {
     let trees = state.values().filter(|&&tile| tile == Tile::Trees);
     let lumberyards = state.values().filter(|&&tile| tile == Tile::Lumberyard);
     trees.count() * lumberyards.count()
 }
 
 pub fn part1(input: &str) -> usize {
-    part_(10, parse(input))
+    let mut state = Dense::new(parse(input), Adjacent::adjacent8, |&tile, adj| {
+        tick(tile, adj)
+    });
+    for _ in 0..10 {
+        state.step();
+    }
+    resource_value(&state.cells)
 }
 
 pub fn part2(input: &str) -> usize {
-    let state = parse(input);
-    let (start, end) = find_cycle(state.clone());
-    part_(start + (1_000_000_000 - start) % (end - start), state)
+    let state = Dense::new(parse(input), Adjacent::adjacent8, |&tile, adj| {
+        tick(tile, adj)
+    });
+    resource_value(state.find_cycle().get(1_000_000_000))
 }
 
 pub fn tests() {
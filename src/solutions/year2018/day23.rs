@@ -1,7 +1,7 @@
 use regex::Regex;
 
 use crate::{
-    grid::{Bounds, Vector3},
+    grid::{Bounds, Metric, Vector3},
     search,
 };
 
@@ -21,72 +21,49 @@ fn parse(input: &str) -> Vec<Bot> {
         .collect()
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Cube {
-    pos: Vector3,
-    w: i64,
-}
-
-impl Cube {
-    // Does the given cube intersect with the given bot's range?
-    fn intersects(self, bot: Bot) -> bool {
-        let mut dist = 0;
-        for axis in 0..3 {
-            if bot.pos[axis] < self.pos[axis] {
-                dist += self.pos[axis] - bot.pos[axis];
-            } else if bot.pos[axis] > self.pos[axis] + self.w - 1 {
-                dist += bot.pos[axis] - (self.pos[axis] + self.w - 1);
-            }
-        }
-        dist <= bot.r
-    }
-
-    fn subcubes(&self) -> impl Iterator<Item = Self> + '_ {
-        [0, self.w / 2].into_iter().flat_map(move |z| {
-            [0, self.w / 2].into_iter().flat_map(move |y| {
-                [0, self.w / 2].into_iter().map(move |x| Self {
-                    pos: self.pos + Vector3::new(x, y, z),
-                    w: self.w / 2,
-                })
-            })
-        })
-    }
+// The manhattan distance from the given point to the nearest point in the given cube.
+fn dist(cube: Bounds<3>, pos: Vector3) -> i64 {
+    (0..3)
+        .map(|axis| (cube.min[axis] - pos[axis]).max(0) + (pos[axis] - cube.max[axis]).max(0))
+        .sum()
 }
 
 pub fn part1(input: &str) -> usize {
     let bots = parse(input);
     let best_bot = bots.iter().max_by_key(|bot| bot.r).unwrap();
     bots.iter()
-        .filter(|bot| (bot.pos - best_bot.pos).abs().sum() <= best_bot.r)
+        .filter(|bot| (bot.pos - best_bot.pos).manhattan() <= best_bot.r)
         .count()
 }
 
-// We can hammer this puzzle in to a dijkstra shaped hole. Start with a cube containing every bot.
-// We can take a "path" to a single coordinate by repeatedly splitting this cube in to 8 smaller
-// cubes of half the width, and choosing one. Let the "cost" of a cube be the number of bots which
-// DON'T intersect with it, then this cost always increases along any path, and the problem reduces
-// to finding a lowest cost path to a cube of width 1.
+// We can hammer this puzzle in to a dijkstra shaped hole. Start with a cube containing every bot's
+// range. We can take a "path" to a single coordinate by repeatedly splitting this cube in to 8
+// smaller cubes of half the width, and choosing one. Let the "cost" of a cube be the number of bots
+// which DON'T intersect with it, then this cost always increases along any path, and the problem
+// reduces to finding a lowest cost path to a cube of width 1.
 pub fn part2(input: &str) -> i64 {
     let bots = parse(input);
-    let bounds: Bounds<3> = bots.iter().map(|bot| bot.pos).collect();
+    let r = bots.iter().map(|bot| bot.r).max().unwrap();
     search::dijkstra(
-        Cube {
-            pos: bounds.min,
-            w: bounds.size().max(),
-        },
-        |cube, push| cube.subcubes().for_each(push),
+        bots.iter()
+            .map(|bot| bot.pos)
+            .collect::<Bounds<3>>()
+            .expand(r),
+        |cube, push| cube.split().into_iter().for_each(push),
         search::id_filter(),
-        move |cube| {
+        move |&cube| {
             (
-                bots.iter().filter(|&&bot| !cube.intersects(bot)).count(),
+                bots.iter()
+                    .filter(|bot| dist(cube, bot.pos) > bot.r)
+                    .count(),
                 // tie break with manhattan distance
-                cube.pos.abs().sum(),
+                dist(cube, Vector3::zeros()),
             )
         },
     )
-    .find(|cube| cube.w == 1)
+    .find(|cube| cube.volume() == 1)
     .unwrap()
-    .pos
+    .min
     .abs()
     .sum()
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::grid::Vector4;
+use crate::grid::{Metric, Vector4};
 
 pub fn part1(input: &str) -> usize {
     let mut constellations: Vec<HashSet<Vector4>> = Vec::new();
@@ -10,7 +10,7 @@ pub fn part1(input: &str) -> usize {
         while i < constellations.len() {
             if constellations[i]
                 .iter()
-                .any(|&p| (p - point).abs().sum() <= 3)
+                .any(|&p| (p - point).manhattan() <= 3)
             {
                 connected.extend(constellations.swap_remove(i));
                 continue;
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
-use ordered_float::OrderedFloat;
-
-use crate::{grid::Vector, uniq::Uniq};
+use crate::{
+    grid::{Bounds, Vector},
+    uniq::Uniq,
+};
 
 fn parse(input: &str) -> Vec<Vector> {
     let mut res = Vec::new();
@@ -16,21 +17,11 @@ fn parse(input: &str) -> Vec<Vector> {
     res
 }
 
-// Angle from the y-axis, in the range (-pi, pi] with zero straight down, and increasing clockwise.
-// Note that this is shifted from the usual definition used in polar coordinates because it makes
-// the problem easier.
-fn theta(asteroid: Vector) -> OrderedFloat<f64> {
-    OrderedFloat(-f64::atan2(
-        i32::try_from(asteroid.x).unwrap().into(),
-        i32::try_from(asteroid.y).unwrap().into(),
-    ))
-}
-
 fn count_visible(asteroids: &[Vector], origin: Vector) -> usize {
     asteroids
         .iter()
         .filter(|&&a| a != origin)
-        .map(|&a| theta(a - origin))
+        .map(|&a| crate::grid::primitive(a - origin))
         .uniq()
         .count()
 }
@@ -49,27 +40,34 @@ pub fn part1(input: &str) -> usize {
 }
 
 pub fn part2(input: &str) -> i64 {
-    let mut asteroids = parse(input);
+    let asteroids = parse(input);
     let origin = origin(&asteroids);
+    let bounds: Bounds<2> = asteroids.iter().copied().collect();
 
-    let mut rays: HashMap<OrderedFloat<f64>, Vec<Vector>> = HashMap::new();
-    for &asteroid in &asteroids {
-        rays.entry(theta(asteroid - origin))
-            .or_default()
-            .push(asteroid);
-    }
-
-    let mut depths: HashMap<Vector, usize> = HashMap::new();
-    for ray in rays.values_mut() {
-        ray.sort_unstable_by_key(|&asteroid| (asteroid - origin).abs().sum());
-        for (depth, &asteroid) in ray.iter().enumerate() {
-            depths.insert(asteroid, depth);
-        }
-    }
+    let mut dirs: Vec<Vector> = asteroids
+        .iter()
+        .filter(|&&a| a != origin)
+        .map(|&a| crate::grid::primitive(a - origin))
+        .uniq()
+        .collect();
+    dirs.sort_unstable_by(|&a, &b| crate::grid::cmp_clockwise(a, b));
 
-    asteroids.sort_unstable_by_key(|&asteroid| (depths[&asteroid], theta(asteroid - origin)));
+    let asteroids: HashSet<Vector> = asteroids.into_iter().collect();
+    let rays: Vec<Vec<Vector>> = dirs
+        .into_iter()
+        .map(|dir| {
+            crate::grid::ray(origin, dir, bounds)
+                .filter(|a| asteroids.contains(a))
+                .collect()
+        })
+        .collect();
 
-    asteroids[199].x * 100 + asteroids[199].y
+    // Each rotation of the laser vaporises the nearest remaining asteroid on every ray.
+    let v = (0..rays.iter().map(Vec::len).max().unwrap_or(0))
+        .flat_map(|depth| rays.iter().filter_map(move |ray| ray.get(depth)))
+        .nth(199)
+        .unwrap();
+    v.x * 100 + v.y
 }
 
 pub fn tests() {
@@ -1,7 +1,8 @@
 use std::collections::{BTreeSet, HashMap};
 
 use crate::{
-    grid::{Adjacent, E, Grid, N, NE, NW, S, SE, SW, Vector, W, Z},
+    graph::Graph,
+    grid::{Adjacent, E, Grid, N, NE, NW, S, SE, SW, W, Z},
     search,
 };
 
@@ -28,57 +29,31 @@ struct Path {
     keys: BTreeSet<char>,
 }
 
-fn reachable(map: &Grid<char>, pos: Vector) -> Vec<Path> {
-    #[derive(Clone)]
-    struct State {
-        pos: Vector,
-        steps: usize,
-        keys: BTreeSet<char>,
-        found_key: bool,
-    }
-
-    search::breadth_first(
-        State {
-            pos,
-            steps: 0,
-            keys: BTreeSet::new(),
-            found_key: false,
-        },
-        |state, push| {
-            if state.found_key {
-                return;
-            }
-            state
-                .pos
-                .adjacent4()
+fn key_graph(map: &Grid<char>) -> HashMap<char, Vec<Path>> {
+    let graph = Graph::from_maze(
+        map.keys().filter(|&pos| is_key(map[pos])),
+        |pos, push| {
+            pos.adjacent4()
                 .filter(|&v| map.get(v).is_some_and(|&tile| is_open(tile)))
-                .for_each(|pos| {
-                    let mut state = state.clone();
-                    state.pos = pos;
-                    state.steps += 1;
-                    if is_door(map[pos]) {
-                        state.keys.insert(key(map[pos]));
-                    } else if is_key(map[pos]) {
-                        state.found_key = true;
-                    }
-                    push(state);
-                });
+                .for_each(push);
         },
-        search::hash_filter(|state: &State| state.pos),
-    )
-    .filter(|state| state.found_key)
-    .map(|state| Path {
-        dest: map[state.pos],
-        steps: state.steps,
-        keys: state.keys,
-    })
-    .collect()
-}
-
-fn key_graph(map: &Grid<char>) -> HashMap<char, Vec<Path>> {
-    map.iter()
-        .filter(|&(_, &tile)| is_key(tile))
-        .map(|(pos, &tile)| (tile, reachable(map, pos)))
+        |pos| is_door(map[pos]),
+    );
+    graph
+        .nodes
+        .iter()
+        .zip(&graph.edges)
+        .map(|(&pos, edges)| {
+            let paths = edges
+                .iter()
+                .map(|edge| Path {
+                    dest: map[graph.nodes[edge.to]],
+                    steps: edge.len,
+                    keys: edge.crossed.iter().map(|&door| key(map[door])).collect(),
+                })
+                .collect();
+            (map[pos], paths)
+        })
         .collect()
 }
 
@@ -6,6 +6,7 @@ use crate::{
 };
 
 struct Maze {
+    size: Vector,
     passages: HashSet<Vector>,
     outer_portals: HashMap<Vector, Vector>,
     inner_portals: HashMap<Vector, Vector>,
@@ -56,6 +57,7 @@ fn parse(input: &str) -> Maze {
     }
 
     Maze {
+        size: g.size,
         passages,
         outer_portals: outer_portals
             .iter()
@@ -103,7 +105,7 @@ pub fn part1(input: &str) -> u32 {
                 });
             }
         },
-        search::hash_filter(|state: &State| state.pos),
+        search::grid_filter(maze.size, |state: &State| state.pos),
     )
     .find(|state| state.pos == maze.finish)
     .unwrap()
@@ -1,10 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    mem,
+use crate::{
+    cycle::Cycle,
+    grid::{Adjacent, Bounds, Grid, GridN, Vector, Vector3},
 };
 
-use crate::grid::{Adjacent, Grid, Vector};
-
 fn biodiversity(bugs: &Grid<bool>) -> u32 {
     bugs.values()
         .enumerate()
@@ -13,94 +11,73 @@ fn biodiversity(bugs: &Grid<bool>) -> u32 {
 }
 
 pub fn part1(input: &str) -> u32 {
-    let mut bugs = Grid::parse(input, |_, c| c == '#');
-    let mut seen = HashSet::new();
-
-    while !seen.contains(&bugs) {
-        seen.insert(bugs.clone());
-        let mut bugs_next = bugs.clone();
-        for (pos, bug) in &bugs {
-            let adjacent = pos
-                .adjacent4()
-                .filter(|&v| bugs.get(v).is_some_and(|&bug| bug))
-                .count();
-            bugs_next[pos] = adjacent == 1 || !bug && adjacent == 2;
-        }
-        bugs = bugs_next;
-    }
-
-    biodiversity(&bugs)
+    let cycle = Cycle::find(
+        Grid::parse(input, |_, c| c == '#'),
+        |bugs| {
+            let mut bugs_next = bugs.clone();
+            for (pos, bug) in &*bugs {
+                let adjacent = pos
+                    .adjacent4()
+                    .filter(|&v| bugs.get(v).is_some_and(|&bug| bug))
+                    .count();
+                bugs_next[pos] = adjacent == 1 || !bug && adjacent == 2;
+            }
+            *bugs = bugs_next;
+        },
+        Clone::clone,
+    );
+    // The first layout to appear twice is the one the cycle starts with.
+    biodiversity(cycle.get(cycle.start))
 }
 
-fn adjacent(bugs: &HashMap<i64, Grid<bool>>, depth: i64, pos: Vector) -> usize {
+// Levels are stacked along the z axis, with the level inside each level one above it.
+fn adjacent(bugs: &GridN<bool, 3>, pos: Vector3) -> usize {
+    let count = |edge: GridN<bool, 1>| edge.iter().filter(|&(_, &bug)| bug).count();
     let mut res = 0;
-    for p in pos.adjacent4() {
+    for p in pos.xy().adjacent4() {
         res += if p.x < 0 {
-            usize::from(bugs[&(depth - 1)][[1, 2]])
+            usize::from(bugs[Vector3::new(1, 2, pos.z - 1)])
         } else if p.y < 0 {
-            usize::from(bugs[&(depth - 1)][[2, 1]])
+            usize::from(bugs[Vector3::new(2, 1, pos.z - 1)])
         } else if p.x > 4 {
-            usize::from(bugs[&(depth - 1)][[3, 2]])
+            usize::from(bugs[Vector3::new(3, 2, pos.z - 1)])
         } else if p.y > 4 {
-            usize::from(bugs[&(depth - 1)][[2, 3]])
+            usize::from(bugs[Vector3::new(2, 3, pos.z - 1)])
         } else if p == Vector::new(2, 2) {
-            match Into::<[i64; 2]>::into(pos) {
-                [2, 1] => (0..5).filter(|&x| bugs[&(depth + 1)][[x, 0]]).count(),
-                [1, 2] => (0..5).filter(|&y| bugs[&(depth + 1)][[0, y]]).count(),
-                [3, 2] => (0..5).filter(|&y| bugs[&(depth + 1)][[4, y]]).count(),
-                [2, 3] => (0..5).filter(|&x| bugs[&(depth + 1)][[x, 4]]).count(),
+            match [pos.x, pos.y] {
+                [2, 1] => count(bugs.slice([0], Vector3::new(0, 0, pos.z + 1))),
+                [1, 2] => count(bugs.slice([1], Vector3::new(0, 0, pos.z + 1))),
+                [3, 2] => count(bugs.slice([1], Vector3::new(4, 0, pos.z + 1))),
+                [2, 3] => count(bugs.slice([0], Vector3::new(0, 4, pos.z + 1))),
                 _ => unreachable!(),
             }
         } else {
-            usize::from(bugs[&depth][p])
+            usize::from(bugs[Vector3::new(p.x, p.y, pos.z)])
         }
     }
     res
 }
 
 fn part2_(minutes: usize, input: &str) -> usize {
-    let mut bugs = HashMap::from([
-        (-2, Grid::new(false, [5, 5])),
-        (-1, Grid::new(false, [5, 5])),
-        (0, Grid::parse(input, |_, c| c == '#')),
-        (1, Grid::new(false, [5, 5])),
-        (2, Grid::new(false, [5, 5])),
-    ]);
-    let mut bugs_next = bugs.clone();
-
-    let mut min_depth = -1;
-    let mut max_depth = 1;
-
+    let level = Grid::parse(input, |_, c| c == '#');
+    // Bugs can't spread more than one level per minute, and we leave an empty level at either end
+    // so that we never look outside the grid.
+    let depth = i64::try_from(minutes).unwrap() + 1;
+    let bounds = Bounds {
+        min: Vector3::new(0, 0, -depth),
+        max: Vector3::new(4, 4, depth),
+    };
+    let mut bugs = GridN::from_fn(bounds, |v| v.z == 0 && level[v.xy()]);
     for _ in 0..minutes {
-        for depth in min_depth..=max_depth {
-            for (pos, bug) in &bugs[&depth] {
-                if pos == Vector::new(2, 2) {
-                    continue;
-                }
-                let adjacent = adjacent(&bugs, depth, pos);
-                bugs_next.get_mut(&depth).unwrap()[pos] = adjacent == 1 || !bug && adjacent == 2;
+        bugs = GridN::from_fn(bounds, |v| {
+            if v.xy() == Vector::new(2, 2) || v.z.abs() == depth {
+                return false;
             }
-        }
-
-        mem::swap(&mut bugs, &mut bugs_next);
-
-        if bugs[&min_depth].values().any(|&bug| bug) {
-            min_depth -= 1;
-            bugs.insert(min_depth - 1, Grid::new(false, [5, 5]));
-            bugs_next.insert(min_depth - 1, Grid::new(false, [5, 5]));
-        }
-
-        if bugs[&max_depth].values().any(|&bug| bug) {
-            max_depth += 1;
-            bugs.insert(max_depth + 1, Grid::new(false, [5, 5]));
-            bugs_next.insert(max_depth + 1, Grid::new(false, [5, 5]));
-        }
+            let adjacent = adjacent(&bugs, v);
+            adjacent == 1 || !bugs[v] && adjacent == 2
+        });
     }
-
-    bugs.values()
-        .flat_map(Grid::values)
-        .filter(|&&bug| bug)
-        .count()
+    bugs.iter().filter(|&(_, &bug)| bug).count()
 }
 
 pub fn part2(input: &str) -> usize {
@@ -1,61 +1,66 @@
-use std::mem;
+use crate::{
+    automaton::{Automaton, Dense},
+    grid::{Adjacent, Grid, Vector, Z},
+};
 
-use crate::grid::{Adjacent, Grid, Vector, Z};
-
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Tile {
     Floor,
     Empty,
     Occupied,
 }
 
-fn part_<F>(input: &str, tolerance: usize, mut adjacent: F) -> usize
-where
-    F: FnMut(&Grid<Tile>, Vector) -> usize,
-{
-    let mut state = Grid::parse(input, |_, c| match c {
+fn parse(input: &str) -> Grid<Tile> {
+    Grid::parse(input, |_, c| match c {
         '.' => Tile::Floor,
         'L' => Tile::Empty,
         '#' => Tile::Occupied,
         _ => unreachable!(),
-    });
-    let mut scrap = Grid::new(Tile::Floor, state.size);
-    while state != scrap {
-        for (pos, &tile) in &state {
-            let adj = adjacent(&state, pos);
-            if tile == Tile::Empty && adj == 0 {
-                scrap[pos] = Tile::Occupied;
-            } else if tile == Tile::Occupied && adj >= tolerance {
-                scrap[pos] = Tile::Empty;
-            } else {
-                scrap[pos] = state[pos];
-            }
+    })
+}
+
+fn part_<F, I>(state: Grid<Tile>, tolerance: usize, adjacent: F) -> usize
+where
+    F: FnMut(Vector) -> I,
+    I: IntoIterator<Item = Vector>,
+{
+    let mut state = Dense::new(state, adjacent, |&tile, adj| {
+        let adj = adj.filter(|&&t| t == Tile::Occupied).count();
+        if tile == Tile::Empty && adj == 0 {
+            Tile::Occupied
+        } else if tile == Tile::Occupied && adj >= tolerance {
+            Tile::Empty
+        } else {
+            tile
         }
-        mem::swap(&mut scrap, &mut state);
-    }
-    state.into_values().filter(|&t| t == Tile::Occupied).count()
+    });
+    state.run_to_fixpoint();
+    state
+        .cells
+        .values()
+        .filter(|&&t| t == Tile::Occupied)
+        .count()
 }
 
 pub fn part1(input: &str) -> usize {
-    part_(input, 4, |state, pos| {
-        pos.adjacent8()
-            .filter(|&v| state.get(v).is_some_and(|&t| t == Tile::Occupied))
-            .count()
-    })
+    part_(parse(input), 4, Adjacent::adjacent8)
 }
 
 pub fn part2(input: &str) -> usize {
-    part_(input, 5, |state, pos| {
+    let state = parse(input);
+    // Floor never changes, so we can work out which seats are visible from each seat up front.
+    let visible = |pos: Vector| {
         Z.adjacent8()
-            .filter(|&dir| {
+            .filter_map(|dir| {
                 let mut pos = pos + dir;
                 while state.get(pos) == Some(&Tile::Floor) {
                     pos += dir;
                 }
-                state.get(pos) == Some(&Tile::Occupied)
+                state.get(pos).map(|_| pos)
             })
-            .count()
-    })
+            .collect::<Vec<_>>()
+    };
+    part_(state.clone(), 5, visible)
 }
 
 pub fn tests() {
@@ -1,5 +1,5 @@
 use crate::{
-    grid::{E, N, Z},
+    grid::{E, Metric, N, Z},
     part::Part,
 };
 
@@ -24,7 +24,7 @@ fn part_(part: Part, input: &str) -> i64 {
             _ => unreachable!(),
         }
     }
-    pos.abs().sum()
+    pos.manhattan()
 }
 
 pub fn part1(input: &str) -> i64 {
@@ -2,7 +2,10 @@ use std::collections::HashSet;
 
 use nalgebra::SVector;
 
-use crate::{grid::Grid, uniq::Uniq};
+use crate::{
+    automaton::{Automaton, Sparse},
+    grid::Grid,
+};
 
 fn neighbors<const D: usize>(v: SVector<i64, D>) -> Vec<SVector<i64, D>> {
     let mut res = vec![v];
@@ -17,27 +20,18 @@ fn neighbors<const D: usize>(v: SVector<i64, D>) -> Vec<SVector<i64, D>> {
     res
 }
 
-fn part_<const D: usize>(mut active: HashSet<SVector<i64, D>>) -> usize {
+fn part_<const D: usize>(active: HashSet<SVector<i64, D>>) -> usize {
+    let mut active = Sparse::new(active, neighbors, |active, active_neighbors| {
+        if active {
+            (2..=3).contains(&active_neighbors)
+        } else {
+            active_neighbors == 3
+        }
+    });
     for _ in 0..6 {
-        active = active
-            .iter()
-            .copied()
-            .flat_map(neighbors)
-            .uniq()
-            .filter(|&v| {
-                let active_neighbors = neighbors(v)
-                    .into_iter()
-                    .filter(|n| active.contains(n))
-                    .count();
-                if active.contains(&v) {
-                    (2..=3).contains(&active_neighbors)
-                } else {
-                    active_neighbors == 3
-                }
-            })
-            .collect();
+        active.step();
     }
-    active.len()
+    active.live.len()
 }
 
 pub fn part1(input: &str) -> usize {
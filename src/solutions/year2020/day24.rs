@@ -2,7 +2,10 @@ use std::{collections::HashSet, sync::LazyLock};
 
 use regex::Regex;
 
-use crate::{grid::Vector, uniq::Uniq};
+use crate::{
+    automaton::{Automaton, Sparse},
+    grid::Vector,
+};
 
 // As described here (in the "pointy" orientation):
 // <https://www.redblobgames.com/grids/hexagons/#coordinates-axial>
@@ -51,23 +54,17 @@ pub fn part1(input: &str) -> usize {
 }
 
 pub fn part2(input: &str) -> usize {
-    let mut black = init(parse(input));
+    let mut black = Sparse::new(init(parse(input)), adjacent, |black, adjacent_count| {
+        if black {
+            [1, 2].contains(&adjacent_count)
+        } else {
+            adjacent_count == 2
+        }
+    });
     for _ in 0..100 {
-        black = black
-            .iter()
-            .flat_map(|&tile| adjacent(tile))
-            .uniq()
-            .filter(|&tile| {
-                let adjacent_count = adjacent(tile).filter(|a| black.contains(a)).count();
-                if black.contains(&tile) {
-                    [1, 2].contains(&adjacent_count)
-                } else {
-                    adjacent_count == 2
-                }
-            })
-            .collect();
+        black.step();
     }
-    black.len()
+    black.live.len()
 }
 
 pub fn tests() {
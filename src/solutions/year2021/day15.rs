@@ -1,34 +1,9 @@
 use std::ops::Div;
 
-use crate::{
-    grid::{Adjacent, Grid, NW, Vector, Z},
-    search,
-};
+use crate::grid::{Grid, NW, Z};
 
 fn search(cave: &Grid<u32>) -> u32 {
-    struct State {
-        pos: Vector,
-        cost: u32,
-    }
-    search::dijkstra(
-        State { pos: Z, cost: 0 },
-        |s, push| {
-            s.pos
-                .adjacent4()
-                .filter_map(|a| {
-                    cave.get(a).map(|risk| State {
-                        pos: a,
-                        cost: s.cost + risk,
-                    })
-                })
-                .for_each(push);
-        },
-        search::hash_filter(|s: &State| s.pos),
-        |s| s.cost,
-    )
-    .find(|s| s.pos == cave.size + NW)
-    .unwrap()
-    .cost
+    cave.weighted_distances([Z], |&risk| Some(risk))[cave.size + NW].unwrap()
 }
 
 pub fn part1(input: &str) -> u32 {
@@ -1,6 +1,10 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::collections::HashMap;
 
-use crate::{freqs::Freqs, grid::Vector3, uniq::Uniq};
+use crate::{
+    freqs::Freqs,
+    grid::{Metric, Vector3},
+    uniq::Uniq,
+};
 
 struct Scanner {
     position: Vector3,
@@ -14,45 +18,13 @@ impl Scanner {
         Self {
             position: Vector3::zeros(),
             fingerprint: crate::combinatorics::combinations(2, &beacons)
-                .map(|pair| (pair[0] - pair[1]).abs().sum())
+                .map(|pair| (pair[0] - pair[1]).manhattan())
                 .freqs(),
             beacons,
         }
     }
 }
 
-static ROTATIONS: LazyLock<[nalgebra::Matrix3<i64>; 24]> = LazyLock::new(|| {
-    let i = nalgebra::matrix![1, 0, 0; 0, 1, 0; 0, 0, 1];
-    let x = nalgebra::matrix![1, 0, 0; 0, 0, -1; 0, 1, 0];
-    let y = nalgebra::matrix![0, 0, 1; 0, 1, 0; -1, 0, 0];
-    [
-        i,
-        x,
-        y,
-        x * x,
-        x * y,
-        y * x,
-        y * y,
-        x * x * x,
-        x * x * y,
-        x * y * x,
-        x * y * y,
-        y * x * x,
-        y * y * x,
-        y * y * y,
-        x * x * x * y,
-        x * x * y * x,
-        x * x * y * y,
-        x * y * x * x,
-        x * y * y * y,
-        y * x * x * x,
-        y * y * y * x,
-        x * x * x * y * x,
-        x * y * x * x * x,
-        x * y * y * y * x,
-    ]
-});
-
 fn parse(input: &str) -> impl Iterator<Item = Scanner> {
     input.trim().split("\n\n").map(|scanner| {
         Scanner::new(
@@ -74,34 +46,23 @@ fn fingerprints_match(a: &HashMap<i64, usize>, b: &HashMap<i64, usize>) -> bool
         >= 66
 }
 
-fn offset(beacons0: &[Vector3], beacons1: &[Vector3]) -> Option<Vector3> {
-    beacons0
-        .iter()
-        .flat_map(|&b0| beacons1.iter().map(move |&b1| b0 - b1))
-        .freqs()
-        .iter()
-        .find(|&(_, &freq)| freq >= 12)
-        .map(|(&offset, _)| offset)
-}
-
 fn fix(fixed_scanners: &[Scanner], floating_scanners: &mut Vec<Scanner>) -> Option<Scanner> {
     for fixed_scanner in fixed_scanners {
         for (i, floating_scanner) in floating_scanners.iter().enumerate() {
             if !fingerprints_match(&fixed_scanner.fingerprint, &floating_scanner.fingerprint) {
                 continue;
             }
-            for rotation in ROTATIONS.iter() {
-                let floating_beacons: Vec<Vector3> = floating_scanner
+            if let Some((rotation, offset)) =
+                crate::grid::align3(&fixed_scanner.beacons, &floating_scanner.beacons, 12)
+            {
+                let mut scanner = floating_scanners.swap_remove(i);
+                scanner.position = offset;
+                scanner.beacons = scanner
                     .beacons
                     .iter()
-                    .map(|b| rotation * b)
+                    .map(|b| rotation * b + offset)
                     .collect();
-                if let Some(offset) = offset(&fixed_scanner.beacons, &floating_beacons) {
-                    let mut scanner = floating_scanners.swap_remove(i);
-                    scanner.position = offset;
-                    scanner.beacons = floating_beacons.iter().map(|b| b + offset).collect();
-                    return Some(scanner);
-                }
+                return Some(scanner);
             }
         }
     }
@@ -131,7 +92,7 @@ pub fn part2(input: &str) -> i64 {
     let scanners: Vec<Vector3> = part_(input).iter().map(|s| s.position).collect();
     scanners
         .iter()
-        .flat_map(|a| scanners.iter().map(move |b| (a - b).abs().sum()))
+        .flat_map(|a| scanners.iter().map(move |b| (a - b).manhattan()))
         .max()
         .unwrap()
 }
@@ -1,54 +1,24 @@
 use regex::Regex;
 
-type Vector = nalgebra::SVector<i64, 3>;
+use crate::grid::{Bounds, BoxSet, Vector3};
 
-#[derive(Clone, Copy, Debug)]
-struct Cuboid {
-    /// inclusive
-    min: Vector,
-    /// exclusive
-    max: Vector,
-}
-
-impl Cuboid {
-    fn volume(self) -> i64 {
-        (self.max - self.min).product()
-    }
-
-    fn intersection(self, other: Self) -> Option<Self> {
-        let res = Self {
-            min: Vector::from_iterator(
-                (0..3).map(|i| num::clamp(other.min[i], self.min[i], self.max[i])),
-            ),
-            max: Vector::from_iterator(
-                (0..3).map(|i| num::clamp(other.max[i], self.min[i], self.max[i])),
-            ),
-        };
-        if res.min.iter().zip(res.max.iter()).all(|(a, b)| a < b) {
-            Some(res)
-        } else {
-            None
-        }
-    }
-}
-
-fn parse(input: &str) -> Vec<(bool, Cuboid)> {
+fn parse(input: &str) -> Vec<(bool, Bounds<3>)> {
     let re =
         Regex::new(r"(on|off) x=(-?\d+)..(-?\d+),y=(-?\d+)..(-?\d+),z=(-?\d+)..(-?\d+)").unwrap();
     re.captures_iter(input)
         .map(|c| {
             (
                 &c[1] == "on",
-                Cuboid {
-                    min: Vector::new(
+                Bounds {
+                    min: Vector3::new(
                         c[2].parse().unwrap(),
                         c[4].parse().unwrap(),
                         c[6].parse().unwrap(),
                     ),
-                    max: Vector::new(
-                        c[3].parse::<i64>().unwrap() + 1,
-                        c[5].parse::<i64>().unwrap() + 1,
-                        c[7].parse::<i64>().unwrap() + 1,
+                    max: Vector3::new(
+                        c[3].parse().unwrap(),
+                        c[5].parse().unwrap(),
+                        c[7].parse().unwrap(),
                     ),
                 },
             )
@@ -56,38 +26,27 @@ fn parse(input: &str) -> Vec<(bool, Cuboid)> {
         .collect()
 }
 
-// The volume contributed by `cuboid` which is not subsequently covered by `rest`.
-fn disjoint_volume(cuboid: Cuboid, rest: &[Cuboid]) -> i64 {
-    let intersections: Vec<Cuboid> = rest.iter().filter_map(|c| c.intersection(cuboid)).collect();
-    cuboid.volume()
-        - (0..intersections.len())
-            .map(|i| disjoint_volume(intersections[i], &intersections[i + 1..]))
-            .sum::<i64>()
-}
-
-fn total_volume(steps: &[(bool, Cuboid)]) -> i64 {
-    (0..steps.len())
-        .filter(|&i| steps[i].0)
-        .map(|i| {
-            disjoint_volume(
-                steps[i].1,
-                &steps[i + 1..].iter().map(|&(_, c)| c).collect::<Vec<_>>(),
-            )
-        })
-        .sum()
+fn total_volume(steps: &[(bool, Bounds<3>)]) -> i64 {
+    let mut cubes = BoxSet::new();
+    for &(on, cuboid) in steps {
+        if on {
+            cubes.add(cuboid);
+        } else {
+            cubes.remove(cuboid);
+        }
+    }
+    cubes.volume()
 }
 
 pub fn part1(input: &str) -> i64 {
+    let region = Bounds {
+        min: Vector3::new(-50, -50, -50),
+        max: Vector3::new(50, 50, 50),
+    };
     total_volume(
         &parse(input)
             .iter()
-            .filter_map(|&(op, c)| {
-                c.intersection(Cuboid {
-                    min: Vector::new(-50, -50, -50),
-                    max: Vector::new(51, 51, 51),
-                })
-                .map(|c| (op, c))
-            })
+            .filter_map(|&(on, c)| c.intersection(&region).map(|c| (on, c)))
             .collect::<Vec<_>>(),
     )
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::grid::{Vector, Z};
+use crate::grid::{Metric, Vector, Z};
 
 struct Motion {
     dir: Vector,
@@ -18,7 +18,7 @@ fn parse(input: &str) -> impl Iterator<Item = Motion> {
 }
 
 fn follow(head: Vector, tail: Vector) -> Vector {
-    if (head - tail).abs().max() > 1 {
+    if (head - tail).chebyshev() > 1 {
         tail + (head - tail).map(i64::signum)
     } else {
         tail
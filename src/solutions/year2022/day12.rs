@@ -6,20 +6,16 @@ struct State {
 }
 
 fn parse(input: &str) -> (Grid<u8>, Vector, Vector) {
-    let mut start = None;
-    let mut target = None;
-    let g = Grid::parse(input, |v, c| match c {
-        'S' => {
-            start = Some(v);
-            b'a'
-        }
-        'E' => {
-            target = Some(v);
-            b'z'
-        }
-        _ => u8::try_from(c).unwrap(),
-    });
-    (g, start.unwrap(), target.unwrap())
+    let (g, markers) = Grid::parse_markers(
+        input,
+        |c| match c {
+            'S' => Some('a'),
+            'E' => Some('z'),
+            _ => None,
+        },
+        |_, c| u8::try_from(c).unwrap(),
+    );
+    (g, markers[&'S'][0], markers[&'E'][0])
 }
 
 // Searching from the target back to the start means we can use the same search for part 2, but find
@@ -40,7 +36,7 @@ fn search_from_target(g: &Grid<u8>, target: Vector) -> impl Iterator<Item = Stat
                 }
             }
         },
-        crate::search::hash_filter(|state: &State| state.pos),
+        crate::search::grid_filter(g.size, |state: &State| state.pos),
     )
 }
 
@@ -1,6 +1,9 @@
 use regex::Regex;
 
-use crate::{grid::Vector, uniq::Uniq};
+use crate::{
+    grid::{Metric, Vector},
+    interval_set::IntervalSet,
+};
 
 #[derive(Clone, Copy)]
 struct Reading {
@@ -19,37 +22,25 @@ fn parse(input: &str) -> Vec<Reading> {
         .collect()
 }
 
-// Cheating a little by assuming there are no gaps. This turns out to be true.
-fn part1_(y: i64, input: &str) -> usize {
+fn part1_(y: i64, input: &str) -> i64 {
     let readings = parse(input);
-    let min_x = readings
-        .iter()
-        .map(|Reading { sensor, beacon }| {
-            let r = (sensor - beacon).abs().sum();
-            sensor.x - (r - (sensor.y - y).abs())
-        })
-        .min()
-        .unwrap();
-    let max_x = readings
-        .iter()
-        .map(|Reading { sensor, beacon }| {
-            let r = (sensor - beacon).abs().sum();
-            sensor.x + (r - (sensor.y - y).abs())
-        })
-        .max()
-        .unwrap();
-    let beacons = readings
-        .iter()
-        .map(|reading| reading.beacon)
-        .filter(|beacon| beacon.y == y)
-        .uniq()
-        .count();
-    usize::try_from(max_x + 1 - min_x).unwrap() - beacons
+    let mut covered = IntervalSet::new();
+    for Reading { sensor, beacon } in &readings {
+        let r = (sensor - beacon).manhattan();
+        let reach = r - (sensor.y - y).abs();
+        covered.insert(sensor.x - reach..=sensor.x + reach);
+    }
+    for reading in &readings {
+        if reading.beacon.y == y {
+            covered.remove(reading.beacon.x..=reading.beacon.x);
+        }
+    }
+    i64::try_from(covered.len()).unwrap()
 }
 
 fn boundary_points(a: Reading, b: Reading) -> impl Iterator<Item = Vector> {
-    let r_a = (a.sensor - a.beacon).abs().sum() + 1;
-    let r_b = (b.sensor - b.beacon).abs().sum() + 1;
+    let r_a = (a.sensor - a.beacon).manhattan() + 1;
+    let r_b = (b.sensor - b.beacon).manhattan() + 1;
     [
         (a.sensor, r_a, b.sensor, r_b),
         (b.sensor, r_b, a.sensor, r_a),
@@ -69,7 +60,7 @@ fn boundary_points(a: Reading, b: Reading) -> impl Iterator<Item = Vector> {
         let a = s0.y - s0.x - sign0 * r0;
         let b = s1.y + s1.x + sign1 * r1;
         let v = Vector::new((b - a) / 2, i64::midpoint(b, a));
-        if (b + a) % 2 == 0 && (v - s0).abs().sum() == r0 && (v - s1).abs().sum() == r1 {
+        if (b + a) % 2 == 0 && (v - s0).manhattan() == r0 && (v - s1).manhattan() == r1 {
             Some(v)
         } else {
             None
@@ -86,14 +77,14 @@ fn part2_(bound: i64, input: &str) -> i64 {
         .find(|v| {
             v.iter().all(|&a| 0 <= a && a <= bound)
                 && readings.iter().all(|reading| {
-                    (reading.sensor - reading.beacon).abs().sum() < (reading.sensor - v).abs().sum()
+                    (reading.sensor - reading.beacon).manhattan() < (reading.sensor - v).manhattan()
                 })
         })
         .unwrap();
     v.x * 4_000_000 + v.y
 }
 
-pub fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> i64 {
     part1_(2_000_000, input)
 }
 
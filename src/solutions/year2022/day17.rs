@@ -1,9 +1,10 @@
-use std::{
-    collections::{HashMap, HashSet},
-    sync::LazyLock,
-};
+use std::{collections::HashSet, sync::LazyLock};
 
-use crate::grid::{E, N, S, Vector};
+use crate::{
+    cycle::Cycle,
+    grid::{E, Grid, N, S, Vector},
+    image::Recorder,
+};
 
 static ROCKS: LazyLock<Vec<Vec<Vector>>> = LazyLock::new(|| {
     "####\n\n.#.\n###\n.#.\n\n..#\n..#\n###\n\n#\n#\n#\n#\n\n##\n##"
@@ -40,6 +41,8 @@ fn push(tower: &HashSet<Vector>, rock: &[Vector], dir: Vector) -> Option<Vec<Vec
 #[derive(Clone, Copy, Debug)]
 struct State {
     height: i64,
+    // How far below the top of the tower the top of each column is.
+    depths: [i64; 7],
     r: usize,
     j: usize,
 }
@@ -50,11 +53,22 @@ fn next<'a, T>(items: &'a [T], i: &mut usize) -> &'a T {
     item
 }
 
+// The top 40 rows of the tower, with up as up.
+fn top(tower: &HashSet<Vector>, height: i64) -> Grid<bool> {
+    Grid::from_iter(
+        [7, 40],
+        (0..40).flat_map(|y| (0..7).map(move |x| tower.contains(&Vector::new(x, height - 1 - y)))),
+    )
+}
+
 fn simulate(jets: &[Vector]) -> impl Iterator<Item = State> {
     let mut tower = HashSet::new();
+    let mut columns = [0; 7];
+    let mut frames = Recorder::from_env("2022-17", 8);
     std::iter::successors(
         Some(State {
             height: 0,
+            depths: [0; 7],
             r: 0,
             j: 0,
         }),
@@ -75,7 +89,18 @@ fn simulate(jets: &[Vector]) -> impl Iterator<Item = State> {
             state.height = state
                 .height
                 .max(rock.iter().map(|v| v.y).max().unwrap() + 1);
+            for v in &rock {
+                let column = &mut columns[usize::try_from(v.x).unwrap()];
+                *column = (*column).max(v.y + 1);
+            }
+            state.depths = columns.map(|column| state.height - column);
             tower.extend(rock);
+            if frames.is_recording() {
+                frames.record(
+                    &top(&tower, state.height),
+                    |&rock| if rock { 255u8 } else { 0 },
+                );
+            }
             Some(state)
         },
     )
@@ -85,26 +110,23 @@ pub fn part1(input: &str) -> i64 {
     simulate(&parse(input)).nth(2022).unwrap().height
 }
 
-// After looking at states where r is a multiple of 5 and grouping by j it's clear that the height
-// settles in to a linear sequence. We can extrapolate from the first pair where the period evenly
-// divides the target.
+// Once the next rock, the next jet, and the shape of the top of the tower repeat, so does everything
+// after, and the tower grows by the same height every time round. The depth of each column stands
+// in for the shape, which is enough in practice since rocks don't slip far under overhangs.
 pub fn part2(input: &str) -> i64 {
     let jets = parse(input);
-    let mut seen: HashMap<usize, State> = HashMap::new();
-    for state in simulate(&jets) {
-        if state.r % ROCKS.len() == 0 {
-            if let Some(prev) = seen.get(&(state.j % jets.len())) {
-                let r_diff = state.r - prev.r;
-                if (1_000_000_000_000 - state.r) % r_diff == 0 {
-                    let height_diff = state.height - prev.height;
-                    let cycles = (1_000_000_000_000 - state.r) / r_diff;
-                    return state.height + i64::try_from(cycles).unwrap() * height_diff;
-                }
-            }
-            seen.insert(state.j % jets.len(), state);
-        }
-    }
-    unreachable!()
+    let mut states = simulate(&jets);
+    let start = states.next().unwrap();
+    let mut heights = vec![start.height];
+    let cycle = Cycle::find(
+        start,
+        |state| {
+            *state = states.next().unwrap();
+            heights.push(state.height);
+        },
+        |state| (state.r % ROCKS.len(), state.j % jets.len(), state.depths),
+    );
+    cycle.extrapolate(1_000_000_000_000, |i| heights[i])
 }
 
 pub fn tests() {
@@ -1,8 +1,6 @@
 use std::collections::HashSet;
 
-use nalgebra::vector;
-
-use crate::grid::{Adjacent3, Bounds, Vector3};
+use crate::grid::{Adjacent3, Bounds, GridN, Vector3};
 
 fn parse(input: &str) -> HashSet<Vector3> {
     input
@@ -23,23 +21,19 @@ pub fn part1(input: &str) -> usize {
 
 pub fn part2(input: &str) -> usize {
     let cubes = parse(input);
-    let mut bounds = Bounds::from(&cubes);
-    bounds.min -= vector![1, 1, 1];
-    bounds.max += vector![1, 1, 1];
-    let exterior: HashSet<_> = crate::search::breadth_first(
-        bounds.min,
-        |cube, push| {
-            cube.adjacent6()
-                .filter(|&c| bounds.contains(c) && !cubes.contains(&c))
-                .for_each(push);
-        },
-        crate::search::id_filter(),
-    )
-    .collect();
+    let lava = GridN::from_fn(Bounds::from(&cubes).expand(1), |v| cubes.contains(&v));
+    let exterior = GridN::from(
+        crate::search::breadth_first(
+            lava.bounds.min,
+            |&cube, push| lava.adjacent(cube).filter(|&c| !lava[c]).for_each(push),
+            crate::search::id_filter(),
+        )
+        .collect::<Vec<_>>(),
+    );
     cubes
         .iter()
         .flat_map(|&cube| cube.adjacent6())
-        .filter(|side| exterior.contains(side))
+        .filter(|&side| exterior.get(side) == Some(&true))
         .count()
 }
 
@@ -118,6 +118,8 @@ fn geodes(minutes: usize, blueprint: &Blueprint) -> usize {
                 push(state);
             }
         },
+        // The minute is part of the state, so this only prunes a state reached again at the same
+        // time, which can't score any better than it did the first time.
         crate::search::id_filter(),
         |state| state.resources.get("geode").copied().unwrap_or_default(),
         // For a crude upper bound, suppose we can build a geode robot in every remaining
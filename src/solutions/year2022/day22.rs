@@ -1,9 +1,8 @@
 use std::{collections::HashMap, sync::LazyLock};
 
-use nalgebra::vector;
 use regex::Regex;
 
-use crate::grid::{E, N, S, SE, Turn, Vector, W};
+use crate::grid::{E, Turn, Vector};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Tile {
@@ -74,43 +73,18 @@ fn step(
     }
 }
 
-// Identify faces by their position on a grid as if each face was a single square:
-//
-//     +---+---+---+---+
-//     |0,0|1,0|2,0|3,0|
-//     +---+---+---+---+
-//     |0,1|1,1|2,1|3,1|
-//     +---+---+---+---+
-//     |0,2|1,2|2,2|3,2|
-//     +---+---+---+---+
-//
-// and then we can generate the full mapping from the seven joins between faces.
-fn portals(side_len: i64, sides: &[(State, State)]) -> HashMap<State, State> {
-    fn edge(side_len: i64, face: Vector, dir: Vector) -> impl DoubleEndedIterator<Item = Vector> {
-        let (s, t) = match crate::cast::vector_to_char(dir) {
-            'N' => (face * side_len, (face + E) * side_len - E),
-            'E' => ((face + E) * side_len - E, (face + SE) * side_len - SE),
-            'S' => ((face + SE) * side_len - SE, (face + S) * side_len - S),
-            'W' => ((face + S) * side_len - S, face * side_len),
-            _ => unreachable!(),
-        };
-        crate::grid::line_segment(s, t)
-    }
-
-    let mut res = HashMap::new();
-    for (a, b) in sides {
-        for (pos_a, pos_b) in edge(side_len, a.pos, a.dir).zip(edge(side_len, b.pos, b.dir).rev()) {
-            res.insert(State::new(pos_a, a.dir), State::new(pos_b, b.dir * -1));
-            res.insert(State::new(pos_b, b.dir), State::new(pos_a, a.dir * -1));
-        }
-    }
-
-    res
-}
-
-fn part_(portals: Option<&HashMap<State, State>>, input: &str) -> i64 {
+fn part_(side_len: Option<i64>, input: &str) -> i64 {
     let (board, instructions) = parse(input);
 
+    let portals: Option<HashMap<State, State>> = side_len.map(|side_len| {
+        crate::grid::cube_net_portals(board.keys().copied(), side_len)
+            .into_iter()
+            .map(|((pos, dir), (portal_pos, portal_dir))| {
+                (State::new(pos, dir), State::new(portal_pos, portal_dir))
+            })
+            .collect()
+    });
+
     let mut state = State::new(
         board
             .keys()
@@ -124,7 +98,7 @@ fn part_(portals: Option<&HashMap<State, State>>, input: &str) -> i64 {
         match instruction {
             Instruction::Forward(n) => {
                 for _ in 0..n {
-                    if let Some(s) = step(&board, portals, state) {
+                    if let Some(s) = step(&board, portals.as_ref(), state) {
                         state = s;
                     } else {
                         break;
@@ -153,21 +127,7 @@ pub fn part1(input: &str) -> i64 {
 }
 
 pub fn part2(input: &str) -> i64 {
-    part_(
-        Some(&portals(
-            50,
-            &[
-                (State::new(vector![1, 0], N), State::new(vector![0, 3], W)),
-                (State::new(vector![1, 0], W), State::new(vector![0, 2], W)),
-                (State::new(vector![2, 0], N), State::new(vector![0, 3], S)),
-                (State::new(vector![2, 0], E), State::new(vector![1, 2], E)),
-                (State::new(vector![2, 0], S), State::new(vector![1, 1], E)),
-                (State::new(vector![1, 1], W), State::new(vector![0, 2], N)),
-                (State::new(vector![1, 2], S), State::new(vector![0, 3], E)),
-            ],
-        )),
-        input,
-    )
+    part_(Some(50), input)
 }
 
 pub fn tests() {
@@ -188,22 +148,5 @@ pub fn tests() {
         "10R5L5R10L4R5L5\n",
     );
     assert_eq!(part1(example), 6032);
-    assert_eq!(
-        part_(
-            Some(&portals(
-                4,
-                &[
-                    (State::new(vector![2, 0], N), State::new(vector![0, 1], N)),
-                    (State::new(vector![2, 0], E), State::new(vector![3, 2], E)),
-                    (State::new(vector![2, 0], W), State::new(vector![1, 1], N)),
-                    (State::new(vector![0, 1], W), State::new(vector![3, 2], S)),
-                    (State::new(vector![0, 1], S), State::new(vector![2, 2], S)),
-                    (State::new(vector![1, 1], S), State::new(vector![2, 2], E)),
-                    (State::new(vector![2, 1], E), State::new(vector![3, 2], N)),
-                ]
-            )),
-            example
-        ),
-        5031
-    );
+    assert_eq!(part_(Some(4), example), 5031);
 }
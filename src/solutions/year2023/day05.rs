@@ -1,145 +1,45 @@
-struct Interval {
-    start: i64,
-    len: i64,
-}
+use crate::interval_set::{IntervalSet, RangeMap};
 
-impl Interval {
-    fn end(&self) -> i64 {
-        self.start + self.len - 1
-    }
-}
-
-struct Map {
-    src: Interval,
-    dst: Interval,
-}
-
-fn parse(input: &str) -> (Vec<i64>, impl Iterator<Item = Vec<Map>> + '_) {
+// Parses the seeds, and composes all the maps into a single map from seed to location.
+fn parse(input: &str) -> (Vec<i64>, RangeMap<i64>) {
     fn parse_nums(s: &str) -> impl Iterator<Item = i64> + '_ {
         s.split_whitespace().map(|n| n.parse().unwrap())
     }
     let (seeds, maps) = input.split_once("\n\n").unwrap();
     (
-        parse_nums(seeds.trim().strip_prefix("seeds: ").unwrap()).collect::<Vec<_>>(),
-        maps.split("\n\n").map(|block| {
-            block
-                .trim()
-                .lines()
-                .skip(1)
-                .map(|line| {
+        parse_nums(seeds.trim().strip_prefix("seeds: ").unwrap()).collect(),
+        maps.split("\n\n")
+            .map(|block| {
+                let mut map = RangeMap::new();
+                for line in block.trim().lines().skip(1) {
                     let mut nums = parse_nums(line);
                     let dst_start = nums.next().unwrap();
                     let src_start = nums.next().unwrap();
                     let len = nums.next().unwrap();
-                    Map {
-                        src: Interval {
-                            start: src_start,
-                            len,
-                        },
-                        dst: Interval {
-                            start: dst_start,
-                            len,
-                        },
-                    }
-                })
-                .collect()
-        }),
+                    map.insert(src_start..=src_start + len - 1, dst_start);
+                }
+                map
+            })
+            .fold(RangeMap::new(), |almanac, map| almanac.then(&map)),
     )
 }
 
 pub fn part1(input: &str) -> i64 {
-    let (seeds, stages) = parse(input);
-    stages
-        .fold(seeds, |seeds, maps| {
-            seeds
-                .into_iter()
-                .map(|seed| {
-                    for map in &maps {
-                        if map.src.start <= seed && seed <= map.src.end() {
-                            return seed - map.src.start + map.dst.start;
-                        }
-                    }
-                    seed
-                })
-                .collect()
-        })
+    let (seeds, almanac) = parse(input);
+    seeds
         .into_iter()
+        .map(|seed| almanac.get(seed))
         .min()
         .unwrap()
 }
 
 pub fn part2(input: &str) -> i64 {
-    let (seeds, stages) = parse(input);
-    stages
-        .fold(
-            seeds
-                .chunks(2)
-                .map(|pair| Interval {
-                    start: pair[0],
-                    len: pair[1],
-                })
-                .collect::<Vec<_>>(),
-            |seeds, maps| {
-                seeds
-                    .into_iter()
-                    .flat_map(|seed| {
-                        // Assume a seed interval is affected by at most one map interval
-                        for map in &maps {
-                            if seed.end() < map.src.start || seed.start > map.src.end() {
-                                // seed and map are disjoint
-                            } else if seed.start >= map.src.start && seed.end() <= map.src.end() {
-                                return vec![Interval {
-                                    start: seed.start - map.src.start + map.dst.start,
-                                    len: seed.len,
-                                }];
-                            } else if seed.start < map.src.start && seed.end() <= map.src.end() {
-                                return vec![
-                                    Interval {
-                                        start: seed.start,
-                                        len: map.src.start - seed.start,
-                                    },
-                                    Interval {
-                                        start: map.dst.start,
-                                        len: seed.len - map.src.start + seed.start,
-                                    },
-                                ];
-                            } else if seed.start >= map.src.start && seed.end() > map.src.end() {
-                                return vec![
-                                    Interval {
-                                        start: seed.start - map.src.start + map.dst.start,
-                                        len: map.src.end() - seed.start,
-                                    },
-                                    Interval {
-                                        start: map.src.start + map.src.len,
-                                        len: seed.len - map.src.end() + seed.start,
-                                    },
-                                ];
-                            } else if seed.start < map.src.start && seed.end() > map.src.end() {
-                                return vec![
-                                    Interval {
-                                        start: seed.start,
-                                        len: map.src.start - seed.start,
-                                    },
-                                    Interval {
-                                        start: map.dst.start,
-                                        len: map.src.len,
-                                    },
-                                    Interval {
-                                        start: map.src.start + map.src.len,
-                                        len: seed.end() - map.src.end(),
-                                    },
-                                ];
-                            }
-                        }
-                        vec![seed]
-                    })
-                    .collect()
-            },
-        )
-        .into_iter()
-        .map(|seed| seed.start)
-        .min()
-        .unwrap()
+    let (seeds, almanac) = parse(input);
+    let seeds: IntervalSet<i64> = seeds
+        .chunks(2)
+        .map(|pair| pair[0]..=pair[0] + pair[1] - 1)
+        .collect();
+    *almanac.apply(&seeds).iter().next().unwrap().start()
 }
 
 pub fn tests() {
@@ -1,9 +1,6 @@
 use std::collections::HashSet;
 
-use crate::{
-    grid::{Adjacent, E, Grid, N, NE, NW, S, SE, SW, Vector, W},
-    search,
-};
+use crate::grid::{Adjacent, E, Grid, N, Polygon, S, Vector, W};
 
 fn parse(input: &str) -> (Vector, Grid<Vec<Vector>>) {
     let mut start = None;
@@ -43,61 +40,14 @@ fn boundary(start: Vector, pipes: &Grid<Vec<Vector>>) -> Vec<Vector> {
     res
 }
 
-fn area(start: Vector, boundary: &HashSet<Vector>) -> impl Iterator<Item = Vector> + '_ {
-    search::breadth_first(
-        start,
-        |&state, push| {
-            state
-                .adjacent4()
-                .filter(|a| !boundary.contains(a))
-                .for_each(push);
-        },
-        search::id_filter(),
-    )
-}
-
-fn count_area(start: Vector, boundary: &HashSet<Vector>, size: Vector) -> Option<usize> {
-    let mut count = 0;
-    for pos in area(start, boundary) {
-        if pos.x == 0 || pos.y == 0 || pos.x == size.x - 1 || pos.y == size.y - 1 {
-            // This is the outside area not the inside
-            return None;
-        }
-        // Only count even points since they correspond to points before expansion
-        if pos.x % 2 == 0 && pos.y % 2 == 0 {
-            count += 1;
-        }
-    }
-    Some(count)
-}
-
 pub fn part1(input: &str) -> usize {
     let (start, pipes) = parse(input);
     boundary(start, &pipes).len() / 2
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> i64 {
     let (start, pipes) = parse(input);
-    let boundary = boundary(start, &pipes);
-
-    // Expand the boundary so that there are empty spaces "between the pipes".
-    let boundary = boundary
-        .windows(2)
-        .flat_map(|pair| vec![pair[0] * 2, pair[0] + pair[1]])
-        .chain(vec![
-            boundary[boundary.len() - 1] * 2,
-            boundary[boundary.len() - 1] + boundary[0],
-        ])
-        .collect::<HashSet<_>>();
-    let start = start * 2;
-    let size = pipes.size * 2;
-
-    for start in [start + NW, start + NE, start + SE, start + SW] {
-        if let Some(count) = count_area(start, &boundary, size) {
-            return count;
-        }
-    }
-    unreachable!()
+    Polygon::new(boundary(start, &pipes)).interior_points()
 }
 
 pub fn tests() {
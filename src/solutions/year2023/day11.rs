@@ -1,4 +1,4 @@
-use crate::grid::{self, Bounds, Vector};
+use crate::grid::{self, Bounds, Metric, Vector};
 
 fn parse(expansion: i64, input: &str) -> Vec<Vector> {
     let mut galaxies: Vec<_> = grid::scan(input)
@@ -32,7 +32,7 @@ fn part_(expansion: i64, input: &str) -> i64 {
     let mut res = 0;
     for i in 0..galaxies.len() {
         for j in i + 1..galaxies.len() {
-            res += (galaxies[j] - galaxies[i]).abs().sum();
+            res += (galaxies[j] - galaxies[i]).manhattan();
         }
     }
     res
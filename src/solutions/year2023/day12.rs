@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use crate::{memo::Memo, search};
 
 fn parse(input: &str) -> impl Iterator<Item = (Vec<u8>, Vec<usize>)> + '_ {
     input.lines().map(|line| {
@@ -10,61 +10,48 @@ fn parse(input: &str) -> impl Iterator<Item = (Vec<u8>, Vec<usize>)> + '_ {
     })
 }
 
-fn count_arrangements(
-    cache: &mut HashMap<(usize, usize), usize>,
-    springs: &[u8],
-    groups: &[usize],
-) -> usize {
-    fn count_arrangements_inner(
-        cache: &mut HashMap<(usize, usize), usize>,
-        springs: &[u8],
-        groups: &[usize],
-    ) -> usize {
+fn count_arrangements(springs: &[u8], groups: &[usize]) -> usize {
+    // Counts the arrangements of the last s springs into the last g groups.
+    let mut memo = Memo::new(|count, (s, g): (usize, usize)| {
+        let springs = &springs[springs.len() - s..];
+        let groups = &groups[groups.len() - g..];
+
+        let count_starting_group = |count: &mut dyn FnMut((usize, usize)) -> usize| {
+            if groups.is_empty()
+                || springs.len() < groups[0]
+                || springs[1..groups[0]].contains(&b'.')
+            {
+                0
+            } else if springs.len() == groups[0] {
+                count((0, g - 1))
+            } else if springs[groups[0]] != b'#' {
+                count((s - groups[0] - 1, g - 1))
+            } else {
+                0
+            }
+        };
+
         if springs.is_empty() && groups.is_empty() {
             1
         } else if springs.is_empty() {
             0
         } else {
             match springs[0] {
-                b'.' => count_arrangements(cache, &springs[1..], groups),
-                b'#' => count_arrangements_starting_group(cache, springs, groups),
-                b'?' => {
-                    count_arrangements(cache, &springs[1..], groups)
-                        + count_arrangements_starting_group(cache, springs, groups)
-                }
+                b'.' => count((s - 1, g)),
+                b'#' => count_starting_group(count),
+                b'?' => count((s - 1, g)) + count_starting_group(count),
                 _ => unreachable!(),
             }
         }
-    }
-
-    fn count_arrangements_starting_group(
-        cache: &mut HashMap<(usize, usize), usize>,
-        springs: &[u8],
-        groups: &[usize],
-    ) -> usize {
-        if groups.is_empty() || springs.len() < groups[0] || springs[1..groups[0]].contains(&b'.') {
-            0
-        } else if springs.len() == groups[0] {
-            count_arrangements(cache, &springs[groups[0]..], &groups[1..])
-        } else if springs[groups[0]] != b'#' {
-            count_arrangements(cache, &springs[groups[0] + 1..], &groups[1..])
-        } else {
-            0
-        }
-    }
-
-    let key = (springs.len(), groups.len());
-    if let Some(&res) = cache.get(&key) {
-        return res;
-    }
-    let res = count_arrangements_inner(cache, springs, groups);
-    cache.insert(key, res);
+    });
+    let res = memo.get((springs.len(), groups.len()));
+    search::record_cache(memo.len(), memo.hits());
     res
 }
 
 pub fn part1(input: &str) -> usize {
     parse(input)
-        .map(|(springs, groups)| count_arrangements(&mut HashMap::new(), &springs, &groups))
+        .map(|(springs, groups)| count_arrangements(&springs, &groups))
         .sum()
 }
 
@@ -76,7 +63,7 @@ pub fn part2(input: &str) -> usize {
                 unfolded.push(b'?');
                 unfolded.extend_from_slice(&springs);
             }
-            count_arrangements(&mut HashMap::new(), &unfolded, &groups.repeat(5))
+            count_arrangements(&unfolded, &groups.repeat(5))
         })
         .sum()
 }
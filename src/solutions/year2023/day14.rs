@@ -1,6 +1,7 @@
-use std::collections::HashMap;
-
-use crate::grid::{E, Grid, N, S, Vector, W};
+use crate::{
+    cycle::Cycle,
+    grid::{E, Grid, N, S, Vector, W},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Tile {
@@ -67,24 +68,7 @@ pub fn part2(input: &str) -> i64 {
         }
     }
 
-    fn find_cycle(platform: &mut Grid<Tile>) -> (usize, usize) {
-        let mut seen = HashMap::new();
-        for spins in 0.. {
-            if let Some(start) = seen.get(platform) {
-                return (spins, spins - start);
-            }
-            seen.insert(platform.clone(), spins);
-            spin(platform);
-        }
-        unreachable!()
-    }
-
-    let mut platform = parse(input);
-    let (spins, period) = find_cycle(&mut platform);
-    for _ in 0..(1_000_000_000 - spins) % period {
-        spin(&mut platform);
-    }
-    score(&platform)
+    score(Cycle::find(parse(input), spin, Clone::clone).get(1_000_000_000))
 }
 
 pub fn tests() {
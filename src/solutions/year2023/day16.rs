@@ -43,10 +43,10 @@ fn energize(tiles: &Grid<char>, beam: Packet) -> usize {
                 }
             }
         },
-        search::id_filter(),
+        // Also prunes packets which have left the grid.
+        search::grid_dir_filter(tiles.size, |packet: &Packet| (packet.pos, packet.dir)),
     )
     .map(|packet| packet.pos)
-    .filter(|&pos| tiles.contains_key(pos))
     .uniq()
     .count()
 }
@@ -3,16 +3,16 @@ use crate::{
     search,
 };
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct Crucible {
     pos: Vector,
     dir: Vector,
     straight_len: u8,
-    heat_loss: u32,
 }
 
 impl Crucible {
-    fn step(&self, city: &Grid<u32>, turn: Option<Turn>) -> Option<Self> {
+    // The crucible after its next step, and the heat lost by taking it.
+    fn step(&self, city: &Grid<u32>, turn: Option<Turn>) -> Option<(Self, u32)> {
         let mut crucible = self.clone();
         if let Some(turn) = turn {
             crucible.dir = turn * crucible.dir;
@@ -21,8 +21,8 @@ impl Crucible {
             crucible.straight_len += 1;
         }
         crucible.pos += crucible.dir;
-        crucible.heat_loss += city.get(crucible.pos)?;
-        Some(crucible)
+        let heat_loss = *city.get(crucible.pos)?;
+        Some((crucible, heat_loss))
     }
 }
 
@@ -30,36 +30,30 @@ fn part_(min_straight_len: u8, max_straight_len: u8, input: &str) -> u32 {
     let city = Grid::parse(input, |_, c| c.to_digit(10).unwrap());
     let target = city.size + NW;
 
-    search::dijkstra(
+    search::dijkstra_edges(
         Crucible {
             pos: Z,
             dir: E,
             straight_len: 0,
-            heat_loss: 0,
         },
         move |crucible, push| {
             if crucible.straight_len >= min_straight_len {
-                if let Some(crucible) = crucible.step(&city, Some(LEFT)) {
-                    push(crucible);
-                }
-                if let Some(crucible) = crucible.step(&city, Some(RIGHT)) {
-                    push(crucible);
+                for turn in [LEFT, RIGHT] {
+                    if let Some((crucible, heat_loss)) = crucible.step(&city, Some(turn)) {
+                        push(crucible, heat_loss);
+                    }
                 }
             }
             if crucible.straight_len < max_straight_len
-                && let Some(crucible) = crucible.step(&city, None)
+                && let Some((crucible, heat_loss)) = crucible.step(&city, None)
             {
-                push(crucible);
+                push(crucible, heat_loss);
             }
         },
-        search::hash_filter(|crucible: &Crucible| {
-            (crucible.pos, crucible.dir, crucible.straight_len)
-        }),
-        |crucible| crucible.heat_loss,
     )
-    .find(|crucible| crucible.pos == target && crucible.straight_len >= min_straight_len)
+    .find(|(crucible, _)| crucible.pos == target && crucible.straight_len >= min_straight_len)
     .unwrap()
-    .heat_loss
+    .1
 }
 
 pub fn part1(input: &str) -> u32 {
@@ -2,7 +2,7 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
-use crate::grid::{E, N, S, Vector, W, Z};
+use crate::grid::{E, N, Polygon, S, Vector, W};
 
 static RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"([UDLR]) (\d+) \(#([0-9a-f]{5})([0-3])\)").unwrap());
@@ -32,24 +32,8 @@ fn parse2(input: &str) -> impl Iterator<Item = (Vector, i64)> + '_ {
 }
 
 fn part_(input: impl Iterator<Item = (Vector, i64)>) -> i64 {
-    let mut pos = Z;
-    let mut boundary = vec![pos];
-    let mut boundary_len = 0;
-    for (dir, side_len) in input {
-        pos += dir * side_len;
-        boundary.push(pos);
-        boundary_len += side_len;
-    }
-
-    // https://en.wikipedia.org/wiki/Shoelace_formula
-    let mut area = 0;
-    for i in 0..boundary.len() - 1 {
-        area += (boundary[i].y + boundary[i + 1].y) * (boundary[i].x - boundary[i + 1].x);
-    }
-    area /= 2;
-
-    // https://en.wikipedia.org/wiki/Pick%27s_theorem
-    area + boundary_len / 2 + 1
+    let lagoon = Polygon::from_moves(input);
+    lagoon.interior_points() + lagoon.boundary_points()
 }
 
 pub fn part1(input: &str) -> i64 {
@@ -4,17 +4,16 @@ use crate::{
 };
 
 fn parse(input: &str) -> (Grid<bool>, Vector) {
-    let mut start = None;
-    let garden = Grid::parse(input, |pos, c| match c {
-        'S' => {
-            start = Some(pos);
-            true
-        }
-        '.' => true,
-        '#' => false,
-        _ => unreachable!(),
-    });
-    (garden, start.unwrap())
+    let (garden, markers) = Grid::parse_markers(
+        input,
+        |c| (c == 'S').then_some('.'),
+        |_, c| match c {
+            '.' => true,
+            '#' => false,
+            _ => unreachable!(),
+        },
+    );
+    (garden, markers[&'S'][0])
 }
 
 fn part_(garden: &Grid<bool>, start: Vector, max_steps: usize) -> usize {
@@ -1,10 +1,10 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::grid::Vector3;
+use crate::grid::{Bounds, GridN, Vector3};
 
 type Brick = Vec<Vector3>;
 
-fn parse(input: &str) -> (HashMap<usize, Brick>, HashMap<Vector3, usize>) {
+fn parse(input: &str) -> (HashMap<usize, Brick>, GridN<Option<usize>, 3>) {
     let bricks = input
         .lines()
         .map(|line| {
@@ -22,10 +22,13 @@ fn parse(input: &str) -> (HashMap<usize, Brick>, HashMap<Vector3, usize>) {
         .enumerate()
         .collect::<HashMap<_, _>>();
 
-    let mut cubes = HashMap::new();
+    // Bricks can fall as far as the ground, below which is z = 0.
+    let mut bounds: Bounds<3> = bricks.values().flatten().copied().collect();
+    bounds.min.z = 1;
+    let mut cubes = GridN::new(None, bounds);
     for (&i, brick) in &bricks {
         for &cube in brick {
-            cubes.insert(cube, i);
+            cubes[cube] = Some(i);
         }
     }
 
@@ -36,12 +39,12 @@ const UP: Vector3 = Vector3::new(0, 0, 1);
 
 fn supports(
     bricks: &HashMap<usize, Brick>,
-    cubes: &HashMap<Vector3, usize>,
+    cubes: &GridN<Option<usize>, 3>,
     i: usize,
 ) -> HashSet<usize> {
     let mut res = HashSet::new();
     for &cube in &bricks[&i] {
-        if let Some(&j) = cubes.get(&(cube + UP))
+        if let Some(&Some(j)) = cubes.get(cube + UP)
             && j != i
         {
             res.insert(j);
@@ -52,12 +55,12 @@ fn supports(
 
 fn supported_by(
     bricks: &HashMap<usize, Brick>,
-    cubes: &HashMap<Vector3, usize>,
+    cubes: &GridN<Option<usize>, 3>,
     i: usize,
 ) -> HashSet<usize> {
     let mut res = HashSet::new();
     for &cube in &bricks[&i] {
-        if let Some(&j) = cubes.get(&(cube - UP))
+        if let Some(&Some(j)) = cubes.get(cube - UP)
             && j != i
         {
             res.insert(j);
@@ -68,7 +71,7 @@ fn supported_by(
 
 fn settle(
     bricks: &mut HashMap<usize, Brick>,
-    cubes: &mut HashMap<Vector3, usize>,
+    cubes: &mut GridN<Option<usize>, 3>,
 ) -> HashSet<usize> {
     let mut res = HashSet::new();
 
@@ -83,9 +86,9 @@ fn settle(
                 for k in supports(bricks, cubes, i) {
                     q.push_back(k);
                 }
-                cubes.remove(&bricks[&i][j]);
+                cubes[bricks[&i][j]] = None;
                 bricks.get_mut(&i).unwrap()[j] -= UP;
-                cubes.insert(bricks[&i][j], i);
+                cubes[bricks[&i][j]] = Some(i);
             }
             q.push_back(i);
             res.insert(i);
@@ -120,8 +123,8 @@ pub fn part2(input: &str) -> usize {
         .map(|&i| {
             let mut bricks = bricks.clone();
             let mut cubes = cubes.clone();
-            for cube in &bricks[&i] {
-                cubes.remove(cube);
+            for &cube in &bricks[&i] {
+                cubes[cube] = None;
             }
             bricks.remove(&i);
             settle(&mut bricks, &mut cubes).len()
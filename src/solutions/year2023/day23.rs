@@ -1,8 +1,6 @@
-use std::collections::{HashMap, HashSet};
-
 use crate::{
+    graph::Graph,
     grid::{Adjacent, Grid, Vector},
-    search,
 };
 
 #[derive(Clone, Copy, PartialEq)]
@@ -21,67 +19,57 @@ fn parse(input: &str) -> Grid<Tile> {
     })
 }
 
-fn reachable(map: &Grid<Tile>, nodes: &HashSet<Vector>, start: Vector) -> HashMap<Vector, usize> {
-    search::breadth_first(
-        (start, 0),
-        |&(pos, steps), push| {
-            if nodes.contains(&pos) && pos != start {
-                // stop
-            } else if let Tile::Slope(dir) = map[pos] {
-                push((pos + dir, steps + 1));
+fn graph(map: &Grid<Tile>, start: Vector, end: Vector) -> Graph {
+    let mut nodes = vec![start, end];
+    nodes.extend(map.keys().filter(|&pos| {
+        map[pos] != Tile::Forest
+            && pos
+                .adjacent4()
+                .filter(|&v| map.get(v).is_some_and(|&tile| tile != Tile::Forest))
+                .count()
+                >= 3
+    }));
+    Graph::from_maze(
+        nodes,
+        |&pos, push| {
+            if let Tile::Slope(dir) = map[pos] {
+                push(pos + dir);
             } else {
                 pos.adjacent4()
                     .filter(|&v| map.get(v).is_some_and(|&tile| tile != Tile::Forest))
-                    .for_each(|v| push((v, steps + 1)));
+                    .for_each(push);
             }
         },
-        search::hash_filter(|&(pos, _)| pos),
+        |_| false,
     )
-    .filter(|&(pos, _)| nodes.contains(&pos) && pos != start)
-    .collect()
 }
 
-fn graph(map: &Grid<Tile>, start: Vector, end: Vector) -> HashMap<Vector, HashMap<Vector, usize>> {
-    let mut nodes = HashSet::from([start, end]);
-    nodes.extend(map.keys().filter(|&pos| {
-        pos.adjacent4()
-            .filter(|&v| map.get(v).is_some_and(|&tile| tile != Tile::Forest))
-            .count()
-            >= 3
-    }));
-    nodes
+// Depth first search for the longest path to the end, keeping track of the nodes we've visited so
+// far as a bitset.
+fn longest(graph: &Graph, end: usize, node: usize, visited: u64) -> Option<usize> {
+    if node == end {
+        return Some(0);
+    }
+    graph.edges[node]
         .iter()
-        .map(|&node| (node, reachable(map, &nodes, node)))
-        .collect()
-}
-
-fn steps(graph: &HashMap<Vector, HashMap<Vector, usize>>, path: &[Vector]) -> usize {
-    path.windows(2).map(|hop| graph[&hop[0]][&hop[1]]).sum()
+        .filter(|edge| visited & 1 << edge.to == 0)
+        .filter_map(|edge| {
+            longest(graph, end, edge.to, visited | 1 << edge.to).map(|steps| steps + edge.len)
+        })
+        .max()
 }
 
 fn part_(map: &Grid<Tile>) -> usize {
     let start = Vector::new(1, 0);
     let end = map.size - Vector::new(2, 1);
     let graph = graph(map, start, end);
-    search::breadth_first(
-        vec![start],
-        |path, push| {
-            let pos = *path.last().unwrap();
-            if pos != end {
-                for &p in graph[&pos].keys() {
-                    if !path.contains(&p) {
-                        let mut path = path.clone();
-                        path.push(p);
-                        push(path);
-                    }
-                }
-            }
-        },
-        search::no_filter,
+    assert!(graph.nodes.len() <= 64);
+    longest(
+        &graph,
+        graph.index[&end],
+        graph.index[&start],
+        1 << graph.index[&start],
     )
-    .filter(|path| *path.last().unwrap() == end)
-    .map(|path| steps(&graph, &path))
-    .max()
     .unwrap()
 }
 
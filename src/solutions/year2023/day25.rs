@@ -12,27 +12,13 @@ fn shortest_path<'a>(
     start: &'a str,
     goal: &'a str,
 ) -> Vec<&'a str> {
-    struct State<'a> {
-        pos: &'a str,
-        path: Vec<&'a str>,
-    }
-    search::breadth_first(
-        State {
-            pos: start,
-            path: vec![start],
-        },
-        |state, push| {
-            for &pos in &graph[state.pos] {
-                let mut path = state.path.clone();
-                path.push(pos);
-                push(State { pos, path });
-            }
-        },
-        search::hash_filter(|state: &State| state.pos),
-    )
-    .find(|state| state.pos == goal)
-    .unwrap()
-    .path
+    let mut search = search::breadth_first_paths(
+        start,
+        |&pos, push| graph[pos].iter().copied().for_each(push),
+        |&pos| pos,
+    );
+    let goal = search.find(|&pos| pos == goal).unwrap();
+    search.path(&goal).into_iter().copied().collect()
 }
 
 fn component_size(graph: &HashMap<&str, HashSet<&str>>, start: &str) -> usize {
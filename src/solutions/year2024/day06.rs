@@ -12,17 +12,22 @@ struct Guard {
 }
 
 fn parse(input: &str) -> (Grid<bool>, Guard) {
-    let mut guard = None;
-    let obstructions = Grid::parse(input, |pos, c| match c {
-        '#' => true,
-        '.' => false,
-        '^' => {
-            guard = Some(Guard { pos, dir: N });
-            false
-        }
-        _ => unreachable!(),
-    });
-    (obstructions, guard.unwrap())
+    let (obstructions, markers) = Grid::parse_markers(
+        input,
+        |c| (c == '^').then_some('.'),
+        |_, c| match c {
+            '#' => true,
+            '.' => false,
+            _ => unreachable!(),
+        },
+    );
+    (
+        obstructions,
+        Guard {
+            pos: markers[&'^'][0],
+            dir: N,
+        },
+    )
 }
 
 fn walk(obstructions: &Grid<bool>, mut guard: Guard) -> (HashSet<Guard>, bool) {
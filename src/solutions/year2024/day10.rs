@@ -1,48 +1,43 @@
-use std::hash::Hash;
-
 use crate::{
     grid::{Adjacent, Grid, Vector},
     search,
 };
 
-fn score<H, K>(grid: &Grid<u32>, v: Vector, hash_key: H) -> usize
-where
-    H: FnMut(&Vec<Vector>) -> K,
-    K: Eq + Hash,
-{
-    search::breadth_first(
-        vec![v],
-        |path, push| {
-            let v = *path.last().unwrap();
+// The number of distinct hiking trails to each summit reachable from the given trailhead. Height
+// increases by exactly one with every step, so every trail to a given summit is a shortest path.
+fn trails(grid: &Grid<u32>, trailhead: Vector) -> Vec<usize> {
+    let mut search = search::shortest_paths(
+        trailhead,
+        |&v, push| {
             let height = grid[v];
             v.adjacent4()
                 .filter(|&v| grid.get(v).is_some_and(|&h| h == height + 1))
-                .for_each(|v| {
-                    let mut path = path.clone();
-                    path.push(v);
-                    push(path);
-                });
+                .for_each(push);
         },
-        search::hash_filter(hash_key),
-    )
-    .filter(|path| grid[*path.last().unwrap()] == 9)
-    .count()
+        |&v| v,
+        |&v| grid[v],
+    );
+    let mut res = Vec::new();
+    while let Some(v) = search.next() {
+        if grid[v] == 9 {
+            res.push(search.path_count(&v));
+        }
+    }
+    res
+}
+
+fn trailheads(grid: &Grid<u32>) -> impl Iterator<Item = Vector> + '_ {
+    grid.keys().filter(|&v| grid[v] == 0)
 }
 
 pub fn part1(input: &str) -> usize {
     let grid = Grid::parse(input, |_, c| c.to_digit(10).unwrap());
-    grid.keys()
-        .filter(|&v| grid[v] == 0)
-        .map(|v| score(&grid, v, |path| *path.last().unwrap()))
-        .sum()
+    trailheads(&grid).map(|v| trails(&grid, v).len()).sum()
 }
 
 pub fn part2(input: &str) -> usize {
     let grid = Grid::parse(input, |_, c| c.to_digit(10).unwrap());
-    grid.keys()
-        .filter(|&v| grid[v] == 0)
-        .map(|v| score(&grid, v, Clone::clone))
-        .sum()
+    trailheads(&grid).flat_map(|v| trails(&grid, v)).sum()
 }
 
 pub fn tests() {
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
 use crate::{
-    grid::{E, Grid, LEFT, RIGHT, Turn, Vector},
+    grid::{E, Grid, LEFT, RIGHT, Vector},
     search,
-    uniq::Uniq,
 };
 
 #[derive(Clone)]
@@ -11,88 +10,72 @@ struct State {
     pos: Vector,
     dir: Vector,
     score: usize,
-    path: Vec<Vector>,
 }
 
-impl State {
-    fn step(&mut self) {
-        self.pos += self.dir;
-        self.score += 1;
-        self.path.push(self.pos);
+fn start(maze: &Grid<char>) -> State {
+    State {
+        pos: maze.keys().find(|&v| maze[v] == 'S').unwrap(),
+        dir: E,
+        score: 0,
     }
+}
 
-    fn run(&self, maze: &Grid<char>) -> Self {
-        let mut state = self.clone();
-        state.step();
-        while maze[state.pos + state.dir] != '#'
-            && maze[state.pos + LEFT * state.dir] == '#'
-            && maze[state.pos + RIGHT * state.dir] == '#'
-        {
-            state.step();
+fn adjacent(maze: &Grid<char>, state: &State, push: &mut dyn FnMut(State)) {
+    if maze[state.pos] != 'E' {
+        if maze[state.pos + state.dir] != '#' {
+            push(State {
+                pos: state.pos + state.dir,
+                score: state.score + 1,
+                ..*state
+            });
+        }
+        for turn in [LEFT, RIGHT] {
+            if maze[state.pos + turn * state.dir] != '#' {
+                push(State {
+                    dir: turn * state.dir,
+                    score: state.score + 1000,
+                    ..*state
+                });
+            }
         }
-        state
-    }
-
-    fn turn(&self, turn: Turn) -> Self {
-        let mut state = self.clone();
-        state.dir = turn * state.dir;
-        state.score += 1000;
-        state
     }
 }
 
-fn search(maze: &Grid<char>) -> impl Iterator<Item = State> + '_ {
-    let start = maze.keys().find(|&v| maze[v] == 'S').unwrap();
-    let mut lowest_score = HashMap::new();
+pub fn part1(input: &str) -> usize {
+    let maze = Grid::parse(input, |_, c| c);
     search::dijkstra(
-        State {
-            pos: start,
-            dir: E,
-            score: 0,
-            path: vec![start],
-        },
-        |state, push| {
-            if maze[state.pos] != 'E' {
-                if maze[state.pos + state.dir] != '#' {
-                    push(state.run(maze));
-                }
-                for turn in [LEFT, RIGHT] {
-                    if maze[state.pos + turn * state.dir] != '#' {
-                        push(state.turn(turn).run(maze));
-                    }
-                }
-            }
-        },
-        // Track the lowest score that we see each (pos, dir) and only consider optimal branches.
-        move |state| {
-            if let Some(&score) = lowest_score.get(&(state.pos, state.dir)) {
-                state.score == score
-            } else {
-                lowest_score.insert((state.pos, state.dir), state.score);
-                true
-            }
-        },
+        start(&maze),
+        |state, push| adjacent(&maze, state, push),
+        search::grid_dir_filter(maze.size, |state: &State| (state.pos, state.dir)),
         |state| state.score,
     )
-}
-
-pub fn part1(input: &str) -> usize {
-    let maze = Grid::parse(input, |_, c| c);
-
-    search(&maze)
-        .find(|state| maze[state.pos] == 'E')
-        .unwrap()
-        .score
+    .find(|state| maze[state.pos] == 'E')
+    .unwrap()
+    .score
 }
 
 pub fn part2(input: &str) -> usize {
-    let min_score = part1(input);
     let maze = Grid::parse(input, |_, c| c);
-    search(&maze)
-        .filter(|state| state.score == min_score && maze[state.pos] == 'E')
-        .flat_map(|state| state.path)
-        .uniq()
-        .count()
+    let mut search = search::shortest_paths(
+        start(&maze),
+        |state, push| adjacent(&maze, state, push),
+        |state| (state.pos, state.dir),
+        |state| state.score,
+    );
+    // We might reach the end facing more than one way with the same lowest score.
+    let best = search.find(|state| maze[state.pos] == 'E').unwrap();
+    let mut ends = vec![best.clone()];
+    ends.extend(
+        search
+            .by_ref()
+            .take_while(|state| state.score == best.score)
+            .filter(|state| maze[state.pos] == 'E'),
+    );
+    let mut tiles = HashSet::new();
+    for end in &ends {
+        tiles.extend(search.on_paths(end).into_iter().map(|state| state.pos));
+    }
+    tiles.len()
 }
 
 pub fn tests() {
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use crate::{
     grid::{Adjacent, Vector, Z},
-    search::{self, hash_filter},
+    search,
 };
 
 fn parse(input: &str) -> Vec<Vector> {
@@ -13,19 +13,15 @@ fn parse(input: &str) -> Vec<Vector> {
 }
 
 fn search(size: i64, corrupted: &HashSet<Vector>) -> Option<usize> {
-    search::breadth_first(
-        (Z, 0),
-        |&(pos, steps), push| {
-            pos.adjacent4()
-                .filter(|a| {
-                    a.x >= 0 && a.y >= 0 && a.x <= size && a.y <= size && !corrupted.contains(a)
-                })
-                .for_each(|a| push((a, steps + 1)));
-        },
-        hash_filter(|&(pos, _)| pos),
-    )
-    .find(|&(pos, _)| pos == Vector::new(size, size))
-    .map(|(_, steps)| steps)
+    let adjacent = |pos: &Vector, push: &mut dyn FnMut(Vector)| {
+        pos.adjacent4()
+            .filter(|a| {
+                a.x >= 0 && a.y >= 0 && a.x <= size && a.y <= size && !corrupted.contains(a)
+            })
+            .for_each(push);
+    };
+    search::bidirectional(Z, Vector::new(size, size), adjacent, adjacent, |&pos| pos)
+        .map(|path| path.len() - 1)
 }
 
 fn part1_(size: i64, bytes: usize, input: &str) -> usize {
@@ -2,59 +2,53 @@ use std::collections::HashMap;
 
 use crate::{
     freqs::Freqs,
-    grid::{Adjacent, Grid, Vector},
-    search,
+    grid::{Grid, Metric, Vector},
 };
 
-fn parse(input: &str) -> (Grid<bool>, Vector) {
-    let mut start = None;
-    let grid = Grid::parse(input, |v, c| match c {
-        '.' | 'E' => true,
-        '#' => false,
-        'S' => {
-            start = Some(v);
-            true
-        }
-        _ => unreachable!(),
-    });
-    (grid, start.unwrap())
+fn parse(input: &str) -> (Grid<bool>, Vector, Vector) {
+    let (grid, markers) = Grid::parse_markers(
+        input,
+        |c| "SE".contains(c).then_some('.'),
+        |_, c| match c {
+            '.' => true,
+            '#' => false,
+            _ => unreachable!(),
+        },
+    );
+    (grid, markers[&'S'][0], markers[&'E'][0])
 }
 
-fn run(grid: &Grid<bool>, start: Vector) -> HashMap<Vector, i64> {
-    search::breadth_first(
-        (start, 0),
-        |&(pos, picoseconds), push| {
-            pos.adjacent4()
-                .filter(|&a| grid.get(a).is_some_and(|&t| t))
-                .for_each(|a| {
-                    push((a, picoseconds + 1));
-                });
-        },
-        search::hash_filter(|&(pos, _)| pos),
-    )
-    .collect()
+// There's only one path through the racetrack, so the shortest path visits every track position.
+fn run(grid: &Grid<bool>, start: Vector, end: Vector) -> HashMap<Vector, i64> {
+    grid.shortest_path(start, end, |&t| t)
+        .unwrap()
+        .into_iter()
+        .zip(0..)
+        .collect()
 }
 
 fn cheats(track: &HashMap<Vector, i64>, max_duration: i64) -> impl Iterator<Item = i64> + '_ {
     track
         .keys()
-        .flat_map(|&a| track.keys().map(move |&b| (a, b, (b - a).abs().sum())))
-        .filter(move |&(a, b, duration)| {
-            duration <= max_duration && track[&b] > track[&a] + duration
+        .flat_map(move |&a| {
+            crate::grid::manhattan_ball(a, max_duration)
+                .filter(|b| track.contains_key(b))
+                .map(move |b| (a, b, (b - a).manhattan()))
         })
-        .map(move |(a, b, duration)| track[&b] - track[&a] - duration)
+        .filter(|&(a, b, duration)| track[&b] > track[&a] + duration)
+        .map(|(a, b, duration)| track[&b] - track[&a] - duration)
 }
 
 pub fn part1(input: &str) -> usize {
-    let (grid, start) = parse(input);
-    cheats(&run(&grid, start), 2)
+    let (grid, start, end) = parse(input);
+    cheats(&run(&grid, start, end), 2)
         .filter(|&saving| saving >= 100)
         .count()
 }
 
 pub fn part2(input: &str) -> usize {
-    let (grid, start) = parse(input);
-    cheats(&run(&grid, start), 20)
+    let (grid, start, end) = parse(input);
+    cheats(&run(&grid, start, end), 20)
         .filter(|&saving| saving >= 100)
         .count()
 }
@@ -78,10 +72,10 @@ pub fn tests() {
         "###############",
     ]
     .join("\n");
-    let (grid, start) = parse(&example);
+    let (grid, start, end) = parse(&example);
 
     assert_eq!(
-        cheats(&run(&grid, start), 2).freqs(),
+        cheats(&run(&grid, start, end), 2).freqs(),
         HashMap::from([
             (2, 14),
             (4, 14),
@@ -97,7 +91,7 @@ pub fn tests() {
         ])
     );
     assert_eq!(
-        cheats(&run(&grid, start), 20)
+        cheats(&run(&grid, start, end), 20)
             .filter(|&saving| saving >= 50)
             .freqs(),
         HashMap::from([
@@ -2,6 +2,7 @@ use std::{collections::HashMap, sync::LazyLock};
 
 use crate::{
     grid::{self, Vector},
+    memo::Memo,
     search,
 };
 
@@ -13,74 +14,74 @@ static NUM: LazyLock<HashMap<Vector, char>> = LazyLock::new(|| parse_keypad("789
 
 static DIR: LazyLock<HashMap<Vector, char>> = LazyLock::new(|| parse_keypad(" ^A\n<v>"));
 
-fn key_cost(
-    cache: &mut HashMap<(usize, char, char), usize>,
-    keypad: &HashMap<Vector, char>,
-    depth: usize,
-    start: char,
-    end: char,
-) -> usize {
-    fn go(
-        cache: &mut HashMap<(usize, char, char), usize>,
-        keypad: &HashMap<Vector, char>,
-        depth: usize,
-        start: char,
-        end: char,
-    ) -> usize {
+fn code_cost(robots: usize, code: &str) -> usize {
+    // The cost at some depth of pressing end, having just pressed start. Only the robot at the top
+    // is typing on the numeric keypad.
+    let mut key_cost = Memo::new(|key_cost, (depth, start, end): (usize, char, char)| {
+        // Pressing a direction costs however long the robot below takes to get from the last one,
+        // so that's all a state needs, plus what the moves so far cost with end pressed after them.
+        #[derive(Clone)]
         struct State {
             pos: Vector,
-            code: String,
+            last: char,
+            moves: usize,
+            cost: usize,
         }
         if depth == 0 {
             1
         } else {
-            let code = search::dijkstra(
-                State {
-                    pos: *keypad.keys().find(|pos| keypad[pos] == start).unwrap(),
-                    code: String::new(),
-                },
-                |state, push| {
-                    for dir in "^>v<".chars() {
-                        let pos = state.pos + crate::cast::char_to_vector(dir);
-                        if keypad.contains_key(&pos) {
-                            let mut code = state.code.clone();
-                            code.push(dir);
-                            push(State { pos, code });
+            let keypad: &HashMap<Vector, char> = if depth == robots { &NUM } else { &DIR };
+            let code: String = {
+                let mut search = search::dijkstra_paths(
+                    State {
+                        pos: *keypad.keys().find(|pos| keypad[pos] == start).unwrap(),
+                        last: 'A',
+                        moves: 0,
+                        cost: key_cost((depth - 1, 'A', 'A')),
+                    },
+                    |state, push| {
+                        for dir in "^>v<".chars() {
+                            let pos = state.pos + crate::cast::char_to_vector(dir);
+                            if keypad.contains_key(&pos) {
+                                let moves = state.moves + key_cost((depth - 1, state.last, dir));
+                                push(State {
+                                    pos,
+                                    last: dir,
+                                    moves,
+                                    cost: moves + key_cost((depth - 1, dir, 'A')),
+                                });
+                            }
                         }
-                    }
-                },
-                search::no_filter,
-                |state| code_cost(cache, &DIR, depth - 1, &state.code),
-            )
-            .find(|state| keypad[&state.pos] == end)
-            .unwrap()
-            .code;
-            code_cost(cache, &DIR, depth - 1, &code)
+                    },
+                    |state| (state.pos, state.last),
+                    |state| state.cost,
+                );
+                let goal = search.find(|state| keypad[&state.pos] == end).unwrap();
+                search.path(&goal)[1..]
+                    .iter()
+                    .map(|state| state.last)
+                    .collect()
+            };
+            sequence_cost(key_cost, depth - 1, &code)
         }
-    }
-
-    if let Some(&res) = cache.get(&(depth, start, end)) {
-        res
-    } else {
-        let res = go(cache, keypad, depth, start, end);
-        cache.insert((depth, start, end), res);
-        res
-    }
+    });
+    let res = sequence_cost(&mut |key| key_cost.get(key), robots, code);
+    search::record_cache(key_cost.len(), key_cost.hits());
+    res
 }
 
-fn code_cost(
-    cache: &mut HashMap<(usize, char, char), usize>,
-    keypad: &HashMap<Vector, char>,
+fn sequence_cost(
+    key_cost: &mut dyn FnMut((usize, char, char)) -> usize,
     depth: usize,
     code: &str,
 ) -> usize {
     let mut res = 0;
     let mut start = 'A';
     for c in code.chars() {
-        res += key_cost(cache, keypad, depth, start, c);
+        res += key_cost((depth, start, c));
         start = c;
     }
-    res + key_cost(cache, keypad, depth, start, 'A')
+    res + key_cost((depth, start, 'A'))
 }
 
 pub fn part1(input: &str) -> usize {
@@ -88,7 +89,7 @@ pub fn part1(input: &str) -> usize {
         .split_whitespace()
         .map(|code| {
             let code = &code[..code.len() - 1];
-            code_cost(&mut HashMap::new(), &NUM, 3, code) * code.parse::<usize>().unwrap()
+            code_cost(3, code) * code.parse::<usize>().unwrap()
         })
         .sum()
 }
@@ -98,7 +99,7 @@ pub fn part2(input: &str) -> usize {
         .split_whitespace()
         .map(|code| {
             let code = &code[..code.len() - 1];
-            code_cost(&mut HashMap::new(), &NUM, 26, code) * code.parse::<usize>().unwrap()
+            code_cost(26, code) * code.parse::<usize>().unwrap()
         })
         .sum()
 }
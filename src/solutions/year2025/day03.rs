@@ -1,18 +1,28 @@
+use crate::max_queue::MaxQueue;
+
 // Greedily choose the largest battery that still leaves room for the number of batteries remaining.
-// Optimal because if two numbers differ in a given digit, all subsequent digits are irrelevant.
-fn max_jolts(mut bank: &str, mut batteries: usize) -> u64 {
-    let mut jolts = String::new();
-    while batteries > 0 {
-        batteries -= 1;
-        let (i, c) = bank[..bank.len() - batteries]
-            .char_indices()
-            .rev() // Reverse because we want the leftmost max and max_by_key returns the rightmost.
-            .max_by_key(|&(_, c)| c)
-            .unwrap();
-        bank = &bank[i + 1..];
-        jolts.push(c);
+// Optimal because if two numbers differ in a given digit, all subsequent digits are irrelevant. The
+// batteries we can choose from form a window which slides along the bank, so we can track the
+// leftmost largest battery as we go.
+fn max_jolts(bank: &str, batteries: usize) -> u64 {
+    let bank = bank.as_bytes();
+    let mut window = MaxQueue::new();
+    let mut start = 0;
+    let mut end = 0;
+    let mut jolts = 0;
+    for remaining in (0..batteries).rev() {
+        while end < bank.len() - remaining {
+            window.push(bank[end]);
+            end += 1;
+        }
+        let (i, &battery) = window.max().unwrap();
+        jolts = jolts * 10 + u64::from(battery - b'0');
+        while start <= i {
+            window.pop();
+            start += 1;
+        }
     }
-    jolts.parse().unwrap()
+    jolts
 }
 
 pub fn part1(input: &str) -> u64 {
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::grid::{Adjacent, Vector};
+use crate::{bit_grid::BitGrid, grid::Vector};
 
 fn parse(input: &str) -> HashSet<Vector> {
     crate::grid::scan(input)
@@ -9,29 +9,28 @@ fn parse(input: &str) -> HashSet<Vector> {
         .collect()
 }
 
-fn accessible(rolls: &HashSet<Vector>, roll: &Vector) -> bool {
-    roll.adjacent8().filter(|a| rolls.contains(a)).count() < 4
+// Rolls with fewer than 4 neighbouring rolls.
+fn accessible(rolls: &BitGrid) -> BitGrid {
+    let counts = rolls.neighbour_counts();
+    rolls & &(&(&counts[0] | &counts[1]) | &(&counts[2] | &counts[3]))
 }
 
 pub fn part1(input: &str) -> usize {
-    let rolls = parse(input);
-    rolls.iter().filter(|&r| accessible(&rolls, r)).count()
+    accessible(&BitGrid::from(&parse(input))).count_ones()
 }
 
+// Removing a roll only ever makes other rolls more accessible, so we can remove every accessible
+// roll at once, and end up in the same place as removing them one at a time.
 pub fn part2(input: &str) -> usize {
-    let mut rolls = parse(input);
-    let mut stack: Vec<Vector> = rolls.iter().copied().collect();
-
-    let mut removed = 0;
-    while let Some(roll) = stack.pop() {
-        if rolls.contains(&roll) && accessible(&rolls, &roll) {
-            rolls.remove(&roll);
-            removed += 1;
-            stack.extend(roll.adjacent8().filter(|r| rolls.contains(r)));
+    let mut rolls = BitGrid::from(&parse(input));
+    let initial = rolls.count_ones();
+    loop {
+        let removable = accessible(&rolls);
+        if removable.count_ones() == 0 {
+            return initial - rolls.count_ones();
         }
+        rolls = &rolls ^ &removable;
     }
-
-    removed
 }
 
 pub fn tests() {
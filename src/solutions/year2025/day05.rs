@@ -1,47 +1,26 @@
-fn parse(input: &str) -> (Vec<(u64, u64)>, impl Iterator<Item = u64>) {
+use crate::interval_set::IntervalSet;
+
+fn parse(input: &str) -> (IntervalSet<u64>, impl Iterator<Item = u64>) {
     let (fresh, ids) = input.split_once("\n\n").unwrap();
     (
         fresh
             .lines()
             .map(|line| {
                 let (a, b) = line.split_once('-').unwrap();
-                (a.parse().unwrap(), b.parse().unwrap())
+                a.parse().unwrap()..=b.parse().unwrap()
             })
             .collect(),
         ids.lines().map(|line| line.parse().unwrap()),
     )
 }
 
-fn disjoint((a0, a1): (u64, u64), (b0, b1): (u64, u64)) -> bool {
-    b1 < a0 || a1 < b0
-}
-
-fn union((a0, a1): (u64, u64), (b0, b1): (u64, u64)) -> (u64, u64) {
-    (a0.min(b0), a1.max(b1))
-}
-
 pub fn part1(input: &str) -> usize {
     let (fresh, ids) = parse(input);
-    ids.filter(|id| fresh.iter().any(|(a, b)| (a..=b).contains(&id)))
-        .count()
+    ids.filter(|&id| fresh.contains(id)).count()
 }
 
 pub fn part2(input: &str) -> u64 {
-    let (fresh, _) = parse(input);
-    let mut fresh_disjoint = Vec::new();
-    for mut a in fresh {
-        let mut fresh_disjoint_next = Vec::new();
-        for b in fresh_disjoint {
-            if disjoint(a, b) {
-                fresh_disjoint_next.push(b);
-            } else {
-                a = union(a, b);
-            }
-        }
-        fresh_disjoint_next.push(a);
-        fresh_disjoint = fresh_disjoint_next;
-    }
-    fresh_disjoint.iter().map(|&(b0, b1)| b1 - b0 + 1).sum()
+    u64::try_from(parse(input).0.len()).unwrap()
 }
 
 pub fn tests() {
@@ -8,16 +8,15 @@ enum Tile {
 }
 
 fn parse(input: &str) -> (Grid<Tile>, Vector) {
-    let mut start = None;
-    let g = Grid::parse(input, |v, c| match c {
-        'S' => {
-            start = Some(v);
-            Tile::Empty
-        }
-        '^' => Tile::Splitter,
-        _ => Tile::Empty,
-    });
-    (g, start.unwrap())
+    let (g, markers) = Grid::parse_markers(
+        input,
+        |c| (c == 'S').then_some('.'),
+        |_, c| match c {
+            '^' => Tile::Splitter,
+            _ => Tile::Empty,
+        },
+    );
+    (g, markers[&'S'][0])
 }
 
 fn timelines(cache: &mut HashMap<Vector, usize>, g: &Grid<Tile>, start: Vector) -> usize {
@@ -1,6 +1,6 @@
 use std::{cmp::Reverse, collections::HashSet};
 
-use crate::grid::Vector3;
+use crate::grid::{Metric, Vector3};
 
 #[derive(PartialEq, Clone, Copy)]
 enum Part {
@@ -16,10 +16,6 @@ fn parse(input: &str) -> Vec<Vector3> {
         .collect()
 }
 
-fn sq_dist(a: Vector3, b: Vector3) -> i64 {
-    (a - b).map(|c| c * c).sum()
-}
-
 fn part_(part: Part, input: &str) -> usize {
     let nodes = parse(input);
 
@@ -29,7 +25,7 @@ fn part_(part: Part, input: &str) -> usize {
             pairs.push((nodes[i], nodes[j]));
         }
     }
-    pairs.sort_unstable_by_key(|&(a, b)| sq_dist(a, b));
+    pairs.sort_unstable_by_key(|&(a, b)| (a - b).euclidean_squared());
 
     if let Part::One(limit) = part {
         pairs.truncate(limit);
@@ -1,4 +1,4 @@
-use std::{collections::BTreeSet, sync::LazyLock};
+use std::sync::LazyLock;
 
 use regex::Regex;
 use z3::{Optimize, SatResult, ast::Int};
@@ -22,31 +22,30 @@ fn parse(input: &str) -> impl Iterator<Item = Machine> {
     })
 }
 
+// Pressing a button twice undoes it, so we can search backwards from the target lights with the
+// same buttons.
 pub fn part1(input: &str) -> usize {
     parse(input)
         .map(|machine| {
-            crate::search::breadth_first(
-                BTreeSet::new(),
-                |state, push| {
-                    for b in machine.buttons.iter().filter(|b| !state.contains(b)) {
-                        let mut s = state.clone();
-                        s.insert(b);
-                        push(s);
-                    }
-                },
-                crate::search::id_filter(),
-            )
-            .find(|state| {
-                let mut lights = vec![false; machine.lights.len()];
-                for &button in state {
+            let adjacent = |lights: &Vec<bool>, push: &mut dyn FnMut(Vec<bool>)| {
+                for button in &machine.buttons {
+                    let mut lights = lights.clone();
                     for &i in button {
                         lights[i] = !lights[i];
                     }
+                    push(lights);
                 }
-                lights == machine.lights
-            })
+            };
+            crate::search::bidirectional(
+                vec![false; machine.lights.len()],
+                machine.lights.clone(),
+                adjacent,
+                adjacent,
+                Clone::clone,
+            )
             .unwrap()
             .len()
+                - 1
         })
         .sum()
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::{memo::Memo, search};
+
 fn parse(input: &str) -> HashMap<&str, Vec<&str>> {
     input
         .trim()
@@ -11,45 +13,40 @@ fn parse(input: &str) -> HashMap<&str, Vec<&str>> {
         .collect()
 }
 
-fn count_paths<'a>(
-    cache: &mut HashMap<(&'a str, &'a str), usize>,
-    wires: &HashMap<&'a str, Vec<&'a str>>,
-    from: &'a str,
-    to: &'a str,
-) -> usize {
-    if let Some(&res) = cache.get(&(from, to)) {
-        res
-    } else {
-        let res = if from == to {
+// Counts the paths through every device on any of the routes, in order, sharing the counts between
+// pairs of devices.
+fn count_paths(wires: &HashMap<&str, Vec<&str>>, routes: &[&[&str]]) -> usize {
+    let mut memo = Memo::new(|count, (from, to): (&str, &str)| {
+        if from == to {
             1
         } else if let Some(outputs) = wires.get(from) {
-            outputs
-                .iter()
-                .map(|&output| count_paths(cache, wires, output, to))
-                .sum()
+            outputs.iter().map(|&output| count((output, to))).sum()
         } else {
             0
-        };
-        cache.insert((from, to), res);
-        res
-    }
+        }
+    });
+    let res = routes
+        .iter()
+        .map(|route| {
+            route
+                .windows(2)
+                .map(|pair| memo.get((pair[0], pair[1])))
+                .product::<usize>()
+        })
+        .sum();
+    search::record_cache(memo.len(), memo.hits());
+    res
 }
 
 pub fn part1(input: &str) -> usize {
-    count_paths(&mut HashMap::new(), &parse(input), "you", "out")
+    count_paths(&parse(input), &[&["you", "out"]])
 }
 
 pub fn part2(input: &str) -> usize {
-    let wires = parse(input);
-    let mut cache = HashMap::new();
-    [["svr", "dac", "fft", "out"], ["svr", "fft", "dac", "out"]]
-        .iter()
-        .map(|path| {
-            path.windows(2)
-                .map(|pair| count_paths(&mut cache, &wires, pair[0], pair[1]))
-                .product::<usize>()
-        })
-        .sum()
+    count_paths(
+        &parse(input),
+        &[&["svr", "dac", "fft", "out"], &["svr", "fft", "dac", "out"]],
+    )
 }
 
 pub fn tests() {